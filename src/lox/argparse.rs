@@ -0,0 +1,306 @@
+use crate::tool::generate_ast::LiteralValue;
+
+// Declarative command-line parsing behind `argparse.parse`. The Lox-side
+// spec map is turned into a `Command` first (see builtins/argparse.rs), so
+// everything here works on plain Rust values.
+
+pub struct Command {
+    pub program: String,
+    pub about: Option<String>,
+    pub flags: Vec<Flag>,
+    pub options: Vec<Opt>,
+    pub positionals: Vec<Positional>,
+    pub commands: Vec<Command>,
+}
+
+pub struct Flag {
+    pub name: String,
+    pub short: Option<char>,
+    pub help: Option<String>,
+}
+
+pub struct Opt {
+    pub name: String,
+    pub short: Option<char>,
+    pub help: Option<String>,
+    pub default: LiteralValue,
+    pub number: bool,
+    pub required: bool,
+}
+
+pub struct Positional {
+    pub name: String,
+    pub help: Option<String>,
+    // Some(..) makes the argument optional
+    pub default: Option<LiteralValue>,
+    // Collects every remaining argument into a tuple
+    pub rest: bool,
+}
+
+pub enum Outcome {
+    Parsed(Vec<(String, LiteralValue)>),
+    // `--help` was given, this is the text to show
+    Help(String),
+    // Bad input: the message and the usage line of the (sub)command it was for
+    Usage { message: String, usage: String, program: String },
+}
+
+impl Command {
+    pub fn usage(&self) -> String {
+        let mut usage = format!("Usage: {}", self.program);
+        if !self.flags.is_empty() || !self.options.is_empty() {
+            usage.push_str(" [options]");
+        }
+        for option in self.options.iter().filter(|option| option.required) {
+            usage.push_str(&format!(" --{} <{}>", option.name, option.name));
+        }
+        for positional in &self.positionals {
+            usage.push(' ');
+            usage.push_str(&positional.placeholder());
+        }
+        if !self.commands.is_empty() {
+            usage.push_str(" <command> [args...]");
+        }
+        usage
+    }
+
+    pub fn help(&self) -> String {
+        let mut text = String::new();
+        if let Some(about) = &self.about {
+            text.push_str(&format!("{} - {}\n\n", self.program, about));
+        }
+        text.push_str(&self.usage());
+        text.push('\n');
+
+        let section = |text: &mut String, title: &str, rows: Vec<(String, String)>| {
+            if rows.is_empty() {
+                return;
+            }
+            let width = rows.iter().map(|(left, _)| left.chars().count()).max().unwrap_or(0);
+            text.push_str(&format!("\n{}:\n", title));
+            for (left, right) in rows {
+                let line = format!("  {:<width$}  {}", left, right, width = width);
+                text.push_str(line.trim_end());
+                text.push('\n');
+            }
+        };
+        let positionals = self.positionals.iter()
+            .map(|positional| {
+                let mut help = positional.help.clone().unwrap_or_default();
+                if let Some(default) = positional.default.as_ref().filter(|default| **default != LiteralValue::Nil) {
+                    help = with_default(help, default);
+                }
+                (positional.placeholder(), help)
+            })
+            .collect();
+        section(&mut text, "Arguments", positionals);
+
+        let short = |short: Option<char>| short.map_or("    ".to_string(), |c| format!("-{}, ", c));
+        let mut options: Vec<(String, String)> = self.flags.iter()
+            .map(|flag| (format!("{}--{}", short(flag.short), flag.name), flag.help.clone().unwrap_or_default()))
+            .collect();
+        options.extend(self.options.iter().map(|option| {
+            let mut help = option.help.clone().unwrap_or_default();
+            if option.required {
+                help = format!("{} (required)", help).trim_start().to_string();
+            } else if option.default != LiteralValue::Nil {
+                help = with_default(help, &option.default);
+            }
+            (format!("{}--{} <{}>", short(option.short), option.name, option.name), help)
+        }));
+        options.push(("-h, --help".to_string(), "Show this help and exit".to_string()));
+        section(&mut text, "Options", options);
+
+        let commands = self.commands.iter()
+            .map(|command| (command.name().to_string(), command.about.clone().unwrap_or_default()))
+            .collect();
+        section(&mut text, "Commands", commands);
+        text
+    }
+
+    // The last word of the program, i.e. the subcommand's own name
+    fn name(&self) -> &str {
+        self.program.rsplit(' ').next().unwrap_or(&self.program)
+    }
+
+    fn usage_error(&self, message: String) -> Outcome {
+        Outcome::Usage { message, usage: self.usage(), program: self.program.clone() }
+    }
+
+    fn find_option(&self, long: Option<&str>, short: Option<char>) -> Option<Either<'_>> {
+        let matches = |name: &str, letter: Option<char>| match (long, short) {
+            (Some(long), _) => long == name,
+            (None, Some(short)) => letter == Some(short),
+            (None, None) => false,
+        };
+        if let Some(flag) = self.flags.iter().find(|flag| matches(&flag.name, flag.short)) {
+            return Some(Either::Flag(flag));
+        }
+        self.options.iter().find(|option| matches(&option.name, option.short)).map(Either::Option)
+    }
+
+    pub fn parse(&self, arguments: &[String]) -> Outcome {
+        let mut values: Vec<(String, LiteralValue)> = Vec::new();
+        for flag in &self.flags {
+            set(&mut values, &flag.name, LiteralValue::Boolean(false));
+        }
+        let mut given_options = Vec::new();
+        let mut positionals: Vec<&String> = Vec::new();
+        let mut only_positionals = false;
+        let mut i = 0;
+        while i < arguments.len() {
+            let argument = &arguments[i];
+            i += 1;
+            if only_positionals || argument == "-" || !argument.starts_with('-') {
+                positionals.push(argument);
+                // Everything after the subcommand's name belongs to the subcommand
+                if !self.commands.is_empty() {
+                    break;
+                }
+                continue;
+            }
+            if argument == "--" {
+                only_positionals = true;
+                continue;
+            }
+            if argument == "--help" || argument == "-h" {
+                return Outcome::Help(self.help());
+            }
+            // --name, --name=value, -n, -n value, -nvalue and -abc for several short flags
+            let (found, inline_value, display) = if let Some(long) = argument.strip_prefix("--") {
+                let (name, inline) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                };
+                (self.find_option(Some(name), None), inline, format!("--{}", name))
+            } else {
+                let mut letters = argument[1..].chars();
+                let letter = letters.next().unwrap_or('-');
+                let remainder: String = letters.collect();
+                let found = self.find_option(None, Some(letter));
+                match found {
+                    Some(Either::Flag(_)) if !remainder.is_empty() => {
+                        // -vq: every letter must be a flag
+                        for letter in argument[1..].chars() {
+                            match self.find_option(None, Some(letter)) {
+                                Some(Either::Flag(flag)) => set(&mut values, &flag.name, LiteralValue::Boolean(true)),
+                                _ => return self.usage_error(format!("unknown flag '-{}' in '{}'", letter, argument)),
+                            }
+                        }
+                        continue;
+                    },
+                    _ => (found, Some(remainder).filter(|remainder| !remainder.is_empty()), format!("-{}", letter)),
+                }
+            };
+            match found {
+                None => return self.usage_error(format!("unknown option '{}'", display)),
+                Some(Either::Flag(flag)) => {
+                    if inline_value.is_some() {
+                        return self.usage_error(format!("flag '{}' does not take a value", display));
+                    }
+                    set(&mut values, &flag.name, LiteralValue::Boolean(true));
+                },
+                Some(Either::Option(option)) => {
+                    let text = match inline_value {
+                        Some(text) => text,
+                        None if i < arguments.len() => {
+                            i += 1;
+                            arguments[i - 1].clone()
+                        },
+                        None => return self.usage_error(format!("option '{}' needs a value", display)),
+                    };
+                    let value = if option.number {
+                        match text.trim().parse::<f64>() {
+                            Ok(number) if !text.trim().is_empty() => LiteralValue::Number(number),
+                            _ => return self.usage_error(format!("option '{}' expects a number but got '{}'", display, text)),
+                        }
+                    } else {
+                        LiteralValue::String(text)
+                    };
+                    set(&mut values, &option.name, value);
+                    given_options.push(option.name.as_str());
+                },
+            }
+        }
+
+        for option in &self.options {
+            if !given_options.contains(&option.name.as_str()) {
+                if option.required {
+                    return self.usage_error(format!("missing required option '--{}'", option.name));
+                }
+                set(&mut values, &option.name, option.default.clone());
+            }
+        }
+
+        if !self.commands.is_empty() {
+            let Some(name) = positionals.first() else {
+                return self.usage_error("missing command".to_string());
+            };
+            let Some(command) = self.commands.iter().find(|command| command.name() == name.as_str()) else {
+                let known: Vec<&str> = self.commands.iter().map(|command| command.name()).collect();
+                return self.usage_error(format!("unknown command '{}', expected one of: {}", name, known.join(", ")));
+            };
+            set(&mut values, "command", LiteralValue::String(command.name().to_string()));
+            return match command.parse(&arguments[i..]) {
+                Outcome::Parsed(sub_values) => {
+                    for (name, value) in sub_values {
+                        // Nested commands read as a path, "remote add"
+                        let value = match (&name[..], value, values.iter().find(|(n, _)| n == "command")) {
+                            ("command", LiteralValue::String(sub), Some((_, LiteralValue::String(parent)))) =>
+                                LiteralValue::String(format!("{} {}", parent, sub)),
+                            (_, value, _) => value,
+                        };
+                        set(&mut values, &name, value);
+                    }
+                    Outcome::Parsed(values)
+                },
+                other => other,
+            };
+        }
+
+        let mut remaining = positionals.into_iter();
+        for positional in &self.positionals {
+            if positional.rest {
+                let rest = remaining.by_ref().map(|argument| LiteralValue::String(argument.clone())).collect();
+                set(&mut values, &positional.name, LiteralValue::Tuple(rest));
+                continue;
+            }
+            match (remaining.next(), &positional.default) {
+                (Some(argument), _) => set(&mut values, &positional.name, LiteralValue::String(argument.clone())),
+                (None, Some(default)) => set(&mut values, &positional.name, default.clone()),
+                (None, None) => return self.usage_error(format!("missing argument <{}>", positional.name)),
+            }
+        }
+        if let Some(extra) = remaining.next() {
+            return self.usage_error(format!("unexpected argument '{}'", extra));
+        }
+        Outcome::Parsed(values)
+    }
+}
+
+enum Either<'a> {
+    Flag(&'a Flag),
+    Option(&'a Opt),
+}
+
+impl Positional {
+    fn placeholder(&self) -> String {
+        match (self.rest, &self.default) {
+            (true, _) => format!("[{}...]", self.name),
+            (false, Some(_)) => format!("[{}]", self.name),
+            (false, None) => format!("<{}>", self.name),
+        }
+    }
+}
+
+// Later values win, but the order of first appearance is kept
+fn set(values: &mut Vec<(String, LiteralValue)>, name: &str, value: LiteralValue) {
+    match values.iter_mut().find(|(existing, _)| existing == name) {
+        Some(entry) => entry.1 = value,
+        None => values.push((name.to_string(), value)),
+    }
+}
+
+fn with_default(help: String, default: &LiteralValue) -> String {
+    format!("{} (default: {})", help, default.repr()).trim_start().to_string()
+}
@@ -0,0 +1,240 @@
+use crate::lox::argparse::{Command, Flag, Opt, Outcome, Positional};
+use crate::lox::builtins::native;
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::map::LoxMap;
+use crate::lox::namespace::Namespace;
+use crate::tool::generate_ast::LiteralValue;
+use std::io::Write;
+use std::rc::Rc;
+
+// `argparse.parse(spec)` reads `args` against a declarative spec:
+//
+//     var opts = argparse.parse({
+//         "program": "greet", "about": "Says hello",
+//         "flags": ({"name": "loud", "short": "l", "help": "Shout"},),
+//         "options": ({"name": "times", "short": "n", "type": "number", "default": 1},),
+//         "positionals": ({"name": "who", "default": "world"},),
+//     });
+//
+// The result maps every name to its value. With "commands" (a map from
+// command name to a nested spec) the chosen command's values are merged in
+// and "command" holds its name. `--help` prints the generated help and exits
+// with 0; invalid input prints the usage line and exits with 64.
+pub fn define_globals(environment: &mut Environment) {
+    let mut argparse = Namespace::new("argparse");
+    argparse.define("parse", native("parse", 1..=2, parse));
+    argparse.define("help", native("help", 1..=1, help));
+    environment.define_constant("argparse".to_string(), LiteralValue::Namespace(Rc::new(argparse)));
+}
+
+const USAGE_EXIT_CODE: i32 = 64;
+
+fn parse(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let command = command_from(interpreter, &args[0], "lox".to_string(), &[])?;
+    let argv = match args.get(1) {
+        Some(argv) => argv.clone(),
+        None => interpreter.environment().borrow_mut().get("args").unwrap_or(LiteralValue::Tuple(Vec::new())),
+    };
+    let arguments = match &argv {
+        LiteralValue::Tuple(elements) => elements.iter().map(|element| match element {
+            LiteralValue::String(s) => Ok(s.clone()),
+            other => Err(interpreter.runtime_error(&format!("argparse.parse: arguments must be strings, got {}.", other.repr()))),
+        }).collect::<Result<Vec<_>, _>>()?,
+        other => return Err(interpreter.runtime_error(&format!("argparse.parse: expected a tuple of arguments but got {}.", other.repr()))),
+    };
+    match command.parse(&arguments) {
+        Outcome::Parsed(values) => {
+            let map = LoxMap::new();
+            for (name, value) in values {
+                let _ = map.insert(LiteralValue::String(name), value);
+            }
+            Ok(LiteralValue::Map(Rc::new(map)))
+        },
+        Outcome::Help(text) => {
+            print!("{}", text);
+            let _ = std::io::stdout().flush();
+            Err(Error::Exit(0))
+        },
+        Outcome::Usage { message, usage, program } => {
+            eprintln!("{}: error: {}", program, message);
+            eprintln!("{}", usage);
+            eprintln!("Run '{} --help' for more information.", program);
+            Err(Error::Exit(USAGE_EXIT_CODE))
+        },
+    }
+}
+
+fn help(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let command = command_from(interpreter, &args[0], "lox".to_string(), &[])?;
+    Ok(LiteralValue::String(command.help()))
+}
+
+// Mistakes in the spec are the script's bug, so they are runtime errors rather than usage errors
+fn spec_error(interpreter: &Interpreter, message: String) -> Error {
+    interpreter.runtime_error(&format!("argparse: {}.", message))
+}
+
+fn expect_map(interpreter: &Interpreter, what: &str, value: &LiteralValue) -> Result<Rc<LoxMap>, Error> {
+    match value {
+        LiteralValue::Map(map) => Ok(map.clone()),
+        other => Err(spec_error(interpreter, format!("{} must be a map but got {}", what, other.repr()))),
+    }
+}
+
+// Rejects keys the spec doesn't know, so that typos don't go unnoticed
+fn check_keys(interpreter: &Interpreter, what: &str, map: &LoxMap, allowed: &[&str]) -> Result<(), Error> {
+    for (key, _) in map.entries() {
+        match &key {
+            LiteralValue::String(key) if allowed.contains(&key.as_str()) => {},
+            other => return Err(spec_error(interpreter, format!(
+                "unknown key {} in {}, expected one of: {}", other.repr(), what, allowed.join(", ")))),
+        }
+    }
+    Ok(())
+}
+
+fn string_field(interpreter: &Interpreter, what: &str, map: &LoxMap, key: &str) -> Result<Option<String>, Error> {
+    match map.get(&LiteralValue::String(key.to_string())) {
+        None | Some(LiteralValue::Nil) => Ok(None),
+        Some(LiteralValue::String(s)) => Ok(Some(s)),
+        Some(other) => Err(spec_error(interpreter, format!("\"{}\" of {} must be a string but got {}", key, what, other.repr()))),
+    }
+}
+
+fn bool_field(interpreter: &Interpreter, what: &str, map: &LoxMap, key: &str) -> Result<bool, Error> {
+    match map.get(&LiteralValue::String(key.to_string())) {
+        None | Some(LiteralValue::Nil) => Ok(false),
+        Some(LiteralValue::Boolean(b)) => Ok(b),
+        Some(other) => Err(spec_error(interpreter, format!("\"{}\" of {} must be true or false but got {}", key, what, other.repr()))),
+    }
+}
+
+// The entries of "flags", "options" or "positionals": a tuple of maps, each with a "name"
+fn entries(interpreter: &Interpreter, spec: &LoxMap, key: &str) -> Result<Vec<(String, Rc<LoxMap>)>, Error> {
+    let elements = match spec.get(&LiteralValue::String(key.to_string())) {
+        None | Some(LiteralValue::Nil) => return Ok(Vec::new()),
+        Some(LiteralValue::Tuple(elements)) => elements,
+        Some(other) => return Err(spec_error(interpreter, format!("\"{}\" must be a tuple of maps but got {}", key, other.repr()))),
+    };
+    let mut entries = Vec::with_capacity(elements.len());
+    for element in &elements {
+        let map = expect_map(interpreter, &format!("an entry of \"{}\"", key), element)?;
+        let what = format!("an entry of \"{}\"", key);
+        let Some(name) = string_field(interpreter, &what, &map, "name")? else {
+            return Err(spec_error(interpreter, format!("{} has no \"name\"", what)));
+        };
+        if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+            return Err(spec_error(interpreter, format!("{:?} is not a valid name", name)));
+        }
+        entries.push((name, map));
+    }
+    Ok(entries)
+}
+
+fn short_field(interpreter: &Interpreter, name: &str, map: &LoxMap) -> Result<Option<char>, Error> {
+    let Some(short) = string_field(interpreter, &format!("'{}'", name), map, "short")? else {
+        return Ok(None);
+    };
+    let mut letters = short.chars();
+    match (letters.next(), letters.next()) {
+        (Some(letter), None) if letter.is_alphanumeric() && letter != 'h' => Ok(Some(letter)),
+        _ => Err(spec_error(interpreter, format!("\"short\" of '{}' must be a single letter other than 'h' but got {:?}", name, short))),
+    }
+}
+
+// `taken` holds the names of the enclosing commands, which subcommands can't reuse
+fn command_from(interpreter: &Interpreter, spec: &LiteralValue, program: String, taken: &[String]) -> Result<Command, Error> {
+    let spec = expect_map(interpreter, "the spec", spec)?;
+    check_keys(interpreter, "the spec", &spec, &["program", "about", "flags", "options", "positionals", "commands"])?;
+    let program = match string_field(interpreter, "the spec", &spec, "program")? {
+        Some(name) if taken.is_empty() => name,
+        _ => program,
+    };
+    let mut command = Command {
+        program,
+        about: string_field(interpreter, "the spec", &spec, "about")?,
+        flags: Vec::new(),
+        options: Vec::new(),
+        positionals: Vec::new(),
+        commands: Vec::new(),
+    };
+    let mut names: Vec<String> = taken.to_vec();
+    let mut shorts: Vec<char> = Vec::new();
+    let mut claim = |interpreter: &Interpreter, name: &str, short: Option<char>| -> Result<(), Error> {
+        if names.iter().any(|taken| taken == name) {
+            return Err(spec_error(interpreter, format!("'{}' is declared twice", name)));
+        }
+        names.push(name.to_string());
+        if let Some(short) = short {
+            if shorts.contains(&short) {
+                return Err(spec_error(interpreter, format!("short name '-{}' is used twice", short)));
+            }
+            shorts.push(short);
+        }
+        Ok(())
+    };
+
+    for (name, map) in entries(interpreter, &spec, "flags")? {
+        check_keys(interpreter, &format!("flag '{}'", name), &map, &["name", "short", "help"])?;
+        let short = short_field(interpreter, &name, &map)?;
+        claim(interpreter, &name, short)?;
+        let help = string_field(interpreter, &format!("flag '{}'", name), &map, "help")?;
+        command.flags.push(Flag { name, short, help });
+    }
+    for (name, map) in entries(interpreter, &spec, "options")? {
+        let what = format!("option '{}'", name);
+        check_keys(interpreter, &what, &map, &["name", "short", "help", "default", "type", "required"])?;
+        let short = short_field(interpreter, &name, &map)?;
+        claim(interpreter, &name, short)?;
+        let number = match string_field(interpreter, &what, &map, "type")?.as_deref() {
+            None | Some("string") => false,
+            Some("number") => true,
+            Some(other) => return Err(spec_error(interpreter, format!("\"type\" of {} must be \"string\" or \"number\" but got {:?}", what, other))),
+        };
+        let default = map.get(&LiteralValue::String("default".to_string())).unwrap_or(LiteralValue::Nil);
+        let required = bool_field(interpreter, &what, &map, "required")?;
+        let help = string_field(interpreter, &what, &map, "help")?;
+        command.options.push(Opt { name, short, help, default, number, required });
+    }
+    for (name, map) in entries(interpreter, &spec, "positionals")? {
+        let what = format!("positional '{}'", name);
+        check_keys(interpreter, &what, &map, &["name", "help", "default", "required", "rest"])?;
+        claim(interpreter, &name, None)?;
+        if command.positionals.last().is_some_and(|previous| previous.rest) {
+            return Err(spec_error(interpreter, format!("{} comes after a \"rest\" positional", what)));
+        }
+        let rest = bool_field(interpreter, &what, &map, "rest")?;
+        let default_key = LiteralValue::String("default".to_string());
+        let optional = map.contains_key(&default_key)
+            || matches!(map.get(&LiteralValue::String("required".to_string())), Some(LiteralValue::Boolean(false)));
+        let default = if optional { Some(map.get(&default_key).unwrap_or(LiteralValue::Nil)) } else { None };
+        if default.is_none() && !rest && command.positionals.iter().any(|previous| previous.default.is_some()) {
+            return Err(spec_error(interpreter, format!("required {} comes after an optional one", what)));
+        }
+        let help = string_field(interpreter, &what, &map, "help")?;
+        command.positionals.push(Positional { name, help, default, rest });
+    }
+
+    match spec.get(&LiteralValue::String("commands".to_string())) {
+        None | Some(LiteralValue::Nil) => {},
+        Some(commands) => {
+            let commands = expect_map(interpreter, "\"commands\"", &commands)?;
+            if !command.positionals.is_empty() {
+                return Err(spec_error(interpreter, "a spec can't have both \"positionals\" and \"commands\"".to_string()));
+            }
+            claim(interpreter, "command", None)?;
+            for (name, sub_spec) in commands.entries() {
+                let LiteralValue::String(name) = name else {
+                    return Err(spec_error(interpreter, format!("command names must be strings but got {}", name.repr())));
+                };
+                let sub_program = format!("{} {}", command.program, name);
+                // Values of every command end up in one map, so names must stay unique
+                let enclosing: Vec<String> = names.iter().filter(|name| *name != "command").cloned().collect();
+                command.commands.push(command_from(interpreter, &sub_spec, sub_program, &enclosing)?);
+            }
+        },
+    }
+    Ok(command)
+}
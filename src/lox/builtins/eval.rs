@@ -0,0 +1,58 @@
+use crate::lox::builtins::{expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::{Error, ErrorManager};
+use crate::lox::interpreter::Interpreter;
+use crate::lox::map::LoxMap;
+use crate::lox::parser::Parser;
+use crate::lox::resolver::Resolver;
+use crate::lox::scanner::Scanner;
+use crate::tool::generate_ast::LiteralValue;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("eval".to_string(), native("eval", 1..=1, eval));
+}
+
+// eval("1 + 2") == (true, 3). The source runs in a child of the caller's scope,
+// so its declarations don't leak out. Errors don't stop the script, they come
+// back as (false, error) where error is a map:
+//
+//     {"kind": "syntax" or "runtime", "message": .., "line": .., "column": ..}
+//
+// Lines and columns count from the start of the string; they are nil when unknown.
+fn eval(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let source = expect_string(interpreter, "eval", &args[0])?;
+    // The script's own error state must not change, so static errors go to a separate manager
+    let error_manager = Rc::new(RefCell::new(ErrorManager::new()));
+    error_manager.borrow_mut().silent = true;
+    let tokens = Scanner::new(source, error_manager.clone()).scan_tokens();
+    let statements = Parser::new(tokens, error_manager.clone()).parse();
+    Resolver::new(error_manager.clone()).resolve(&statements);
+
+    let first_error = error_manager.borrow_mut().recorded.drain(..).next();
+    if let Some(error) = first_error {
+        return Ok(failure("syntax", error.message, Some(error.line), error.column));
+    }
+    // Control flow such as `exit()` passes through, only errors are caught
+    match interpreter.with_errors_silenced(|interpreter| interpreter.interpret_in_child_scope(statements)) {
+        Ok(value) => Ok(LiteralValue::Tuple(vec![LiteralValue::Boolean(true), value])),
+        Err(Error::Runtime(message)) | Err(Error::Other(message)) => Ok(failure("runtime", message, None, None)),
+        Err(other) => Err(other),
+    }
+}
+
+fn failure(kind: &str, message: String, line: Option<usize>, column: Option<usize>) -> LiteralValue {
+    let position = |value: Option<usize>| value.map_or(LiteralValue::Nil, |value| LiteralValue::Number(value as f64));
+    let error = LoxMap::new();
+    let fields = [
+        ("kind", LiteralValue::String(kind.to_string())),
+        ("message", LiteralValue::String(message)),
+        ("line", position(line)),
+        ("column", position(column)),
+    ];
+    for (name, value) in fields {
+        let _ = error.insert(LiteralValue::String(name.to_string()), value);
+    }
+    LiteralValue::Tuple(vec![LiteralValue::Boolean(false), LiteralValue::Map(Rc::new(error))])
+}
@@ -0,0 +1,126 @@
+use crate::lox::builtins::{expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::namespace::Namespace;
+use crate::lox::permissions::normalize_path;
+use crate::tool::generate_ast::LiteralValue;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+// File system access under the `fs` namespace. Every call that touches the
+// disk goes through `Permissions::check_fs` first; `join` and `normalize`
+// only manipulate path strings and are always available.
+pub fn define_globals(environment: &mut Environment) {
+    let mut fs = Namespace::new("fs");
+    fs.define("read_file", native("read_file", 1..=1, read_file));
+    fs.define("write_file", native("write_file", 2..=2, write_file));
+    fs.define("append_file", native("append_file", 2..=2, append_file));
+    fs.define("read_lines", native("read_lines", 1..=1, read_lines));
+    fs.define("exists", native("exists", 1..=1, exists));
+    fs.define("list_dir", native("list_dir", 1..=1, list_dir));
+    fs.define("mkdir", native("mkdir", 1..=1, mkdir));
+    fs.define("remove", native("remove", 1..=1, remove));
+    fs.define("join", native("join", 1..=usize::MAX, join));
+    fs.define("normalize", native("normalize", 1..=1, normalize));
+    environment.define_constant("fs".to_string(), LiteralValue::Namespace(Rc::new(fs)));
+}
+
+// Path argument checked against the allowed roots
+fn allowed_path(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<(String, PathBuf), Error> {
+    let path = expect_string(interpreter, function, value)?;
+    match interpreter.permissions.check_fs(&path) {
+        Ok(resolved) => Ok((path, resolved)),
+        Err(message) => Err(interpreter.runtime_error(&format!("fs.{}: {}", function, message))),
+    }
+}
+
+fn io_error(interpreter: &Interpreter, function: &str, path: &str, error: std::io::Error) -> Error {
+    interpreter.runtime_error(&format!("fs.{}: '{}': {}", function, path, error))
+}
+
+fn path_string(path: &Path) -> LiteralValue {
+    LiteralValue::String(path.to_string_lossy().into_owned())
+}
+
+fn read_file(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (path, resolved) = allowed_path(interpreter, "read_file", &args[0])?;
+    std::fs::read_to_string(resolved)
+        .map(LiteralValue::String)
+        .map_err(|e| io_error(interpreter, "read_file", &path, e))
+}
+
+fn write_file(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (path, resolved) = allowed_path(interpreter, "write_file", &args[0])?;
+    let contents = expect_string(interpreter, "write_file", &args[1])?;
+    std::fs::write(resolved, contents)
+        .map(|_| LiteralValue::Nil)
+        .map_err(|e| io_error(interpreter, "write_file", &path, e))
+}
+
+fn append_file(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (path, resolved) = allowed_path(interpreter, "append_file", &args[0])?;
+    let contents = expect_string(interpreter, "append_file", &args[1])?;
+    std::fs::OpenOptions::new().create(true).append(true).open(resolved)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map(|_| LiteralValue::Nil)
+        .map_err(|e| io_error(interpreter, "append_file", &path, e))
+}
+
+fn read_lines(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (path, resolved) = allowed_path(interpreter, "read_lines", &args[0])?;
+    let contents = std::fs::read_to_string(resolved)
+        .map_err(|e| io_error(interpreter, "read_lines", &path, e))?;
+    Ok(LiteralValue::Tuple(contents.lines().map(|line| LiteralValue::String(line.to_string())).collect()))
+}
+
+fn exists(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (_, resolved) = allowed_path(interpreter, "exists", &args[0])?;
+    Ok(LiteralValue::Boolean(resolved.exists()))
+}
+
+// Entry names, sorted so that scripts behave the same on every platform
+fn list_dir(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (path, resolved) = allowed_path(interpreter, "list_dir", &args[0])?;
+    let entries = std::fs::read_dir(resolved)
+        .and_then(|entries| entries.map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned())).collect::<Result<Vec<_>, _>>())
+        .map_err(|e| io_error(interpreter, "list_dir", &path, e))?;
+    let mut names = entries;
+    names.sort();
+    Ok(LiteralValue::Tuple(names.into_iter().map(LiteralValue::String).collect()))
+}
+
+// Creates missing parent directories too
+fn mkdir(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (path, resolved) = allowed_path(interpreter, "mkdir", &args[0])?;
+    std::fs::create_dir_all(resolved)
+        .map(|_| LiteralValue::Nil)
+        .map_err(|e| io_error(interpreter, "mkdir", &path, e))
+}
+
+// Removes a file or an empty directory, never a whole tree.
+// A symlink is removed itself, its target is left alone.
+fn remove(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let path = expect_string(interpreter, "remove", &args[0])?;
+    let entry = interpreter.permissions.check_fs_entry(&path)
+        .map_err(|message| interpreter.runtime_error(&format!("fs.remove: {}", message)))?;
+    let result = match std::fs::symlink_metadata(&entry) {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir(entry),
+        _ => std::fs::remove_file(entry),
+    };
+    result.map(|_| LiteralValue::Nil).map_err(|e| io_error(interpreter, "remove", &path, e))
+}
+
+fn join(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let mut path = PathBuf::new();
+    for part in &args {
+        path.push(expect_string(interpreter, "join", part)?);
+    }
+    Ok(path_string(&path))
+}
+
+fn normalize(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let path = expect_string(interpreter, "normalize", &args[0])?;
+    Ok(path_string(&normalize_path(Path::new(&path))))
+}
@@ -0,0 +1,34 @@
+use crate::lox::builtins::{expect_integer, expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::json;
+use crate::tool::generate_ast::LiteralValue;
+
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("json_parse".to_string(), native("json_parse", 1..=1, json_parse));
+    environment.define_constant("json_stringify".to_string(), native("json_stringify", 1..=2, json_stringify));
+}
+
+fn json_parse(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let text = expect_string(interpreter, "json_parse", &args[0])?;
+    json::parse(&text).map_err(|e| interpreter.runtime_error(&format!("json_parse: {}.", e)))
+}
+
+// Deeper indents only pad the output with whitespace
+const MAX_INDENT: i64 = 10;
+
+// json_stringify(value) is compact, json_stringify(value, 2) indents by two spaces
+fn json_stringify(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let indent = match args.get(1) {
+        None | Some(LiteralValue::Nil) => 0,
+        Some(indent) => expect_integer(interpreter, "json_stringify", indent)?,
+    };
+    if !(0..=MAX_INDENT).contains(&indent) {
+        return Err(interpreter.runtime_error(
+            &format!("json_stringify: indent must be between 0 and {}, got {}.", MAX_INDENT, indent)));
+    }
+    json::stringify(&args[0], indent as usize)
+        .map(LiteralValue::String)
+        .map_err(|message| interpreter.runtime_error(&format!("json_stringify: {}.", message)))
+}
@@ -0,0 +1,76 @@
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::map::LoxMap;
+use crate::lox::native::{NativeFn, NativeFunction};
+use crate::tool::generate_ast::LiteralValue;
+use std::rc::Rc;
+
+pub fn method(name: &str) -> Option<NativeFunction> {
+    let (arity, function): (_, NativeFn) = match name {
+        "get" => (1..=2, get),
+        "set" => (2..=2, set),
+        "has" => (1..=1, has),
+        "remove" => (1..=1, remove),
+        "len" => (0..=0, len),
+        "keys" => (0..=0, keys),
+        "values" => (0..=0, values),
+        "items" => (0..=0, items),
+        _ => return None,
+    };
+    Some(NativeFunction::new(name, arity, function))
+}
+
+fn expect_map(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<Rc<LoxMap>, Error> {
+    match value {
+        LiteralValue::Map(map) => Ok(map.clone()),
+        other => Err(interpreter.runtime_error(
+            &format!("'{}' expects a map but got {}.", function, other.repr()))),
+    }
+}
+
+// m.get(key) is nil for a missing key, m.get(key, default) returns the default instead
+fn get(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "get", &args[0])?;
+    Ok(map.get(&args[1]).unwrap_or_else(|| args.get(2).cloned().unwrap_or(LiteralValue::Nil)))
+}
+
+fn set(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "set", &args[0])?;
+    match map.insert(args[1].clone(), args[2].clone()) {
+        Ok(()) => Ok(LiteralValue::Nil),
+        Err(key) => Err(interpreter.runtime_error(
+            &format!("Map keys must be nil, booleans, numbers, strings or tuples of those, got {}.", key.repr()))),
+    }
+}
+
+fn has(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "has", &args[0])?;
+    Ok(LiteralValue::Boolean(map.contains_key(&args[1])))
+}
+
+// Returns the removed value, or nil when the key was missing
+fn remove(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "remove", &args[0])?;
+    Ok(map.remove(&args[1]).unwrap_or(LiteralValue::Nil))
+}
+
+fn len(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "len", &args[0])?;
+    Ok(LiteralValue::Number(map.len() as f64))
+}
+
+fn keys(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "keys", &args[0])?;
+    Ok(LiteralValue::Tuple(map.entries().into_iter().map(|(key, _)| key).collect()))
+}
+
+fn values(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "values", &args[0])?;
+    Ok(LiteralValue::Tuple(map.entries().into_iter().map(|(_, value)| value).collect()))
+}
+
+// (key, value) pairs, ready for `var (k, v) = pair;`
+fn items(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let map = expect_map(interpreter, "items", &args[0])?;
+    Ok(LiteralValue::Tuple(map.entries().into_iter().map(|(key, value)| LiteralValue::Tuple(vec![key, value])).collect()))
+}
@@ -0,0 +1,126 @@
+use crate::lox::builtins::{expect_number, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::namespace::Namespace;
+use crate::tool::generate_ast::LiteralValue;
+use std::rc::Rc;
+
+// Everything lives under the `math` namespace: math.sqrt(2), math.PI
+pub fn define_globals(environment: &mut Environment) {
+    let mut math = Namespace::new("math");
+    math.define("PI", LiteralValue::Number(std::f64::consts::PI));
+    math.define("E", LiteralValue::Number(std::f64::consts::E));
+    math.define("INF", LiteralValue::Number(f64::INFINITY));
+    math.define("NAN", LiteralValue::Number(f64::NAN));
+
+    math.define("sqrt", native("sqrt", 1..=1, sqrt));
+    math.define("abs", native("abs", 1..=1, abs));
+    math.define("floor", native("floor", 1..=1, floor));
+    math.define("ceil", native("ceil", 1..=1, ceil));
+    math.define("round", native("round", 1..=1, round));
+    math.define("sin", native("sin", 1..=1, sin));
+    math.define("cos", native("cos", 1..=1, cos));
+    math.define("tan", native("tan", 1..=1, tan));
+    math.define("exp", native("exp", 1..=1, exp));
+    math.define("log", native("log", 1..=2, log));
+    math.define("pow", native("pow", 2..=2, pow));
+    math.define("atan2", native("atan2", 2..=2, atan2));
+    math.define("hypot", native("hypot", 2..=2, hypot));
+    math.define("min", native("min", 1..=usize::MAX, min));
+    math.define("max", native("max", 1..=usize::MAX, max));
+    math.define("is_nan", native("is_nan", 1..=1, is_nan));
+    math.define("is_finite", native("is_finite", 1..=1, is_finite));
+
+    environment.define_constant("math".to_string(), LiteralValue::Namespace(Rc::new(math)));
+}
+
+fn numbers(interpreter: &Interpreter, function: &str, args: &[LiteralValue]) -> Result<Vec<f64>, Error> {
+    args.iter().map(|arg| expect_number(interpreter, function, arg)).collect()
+}
+
+fn unary(interpreter: &Interpreter, function: &str, args: &[LiteralValue], operation: fn(f64) -> f64) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Number(operation(expect_number(interpreter, function, &args[0])?)))
+}
+
+fn binary(interpreter: &Interpreter, function: &str, args: &[LiteralValue], operation: fn(f64, f64) -> f64) -> Result<LiteralValue, Error> {
+    let values = numbers(interpreter, function, args)?;
+    Ok(LiteralValue::Number(operation(values[0], values[1])))
+}
+
+fn sqrt(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "sqrt", &args, f64::sqrt)
+}
+
+fn abs(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "abs", &args, f64::abs)
+}
+
+fn floor(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "floor", &args, f64::floor)
+}
+
+fn ceil(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "ceil", &args, f64::ceil)
+}
+
+// Halfway cases round away from zero
+fn round(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "round", &args, f64::round)
+}
+
+fn sin(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "sin", &args, f64::sin)
+}
+
+fn cos(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "cos", &args, f64::cos)
+}
+
+fn tan(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "tan", &args, f64::tan)
+}
+
+fn exp(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    unary(interpreter, "exp", &args, f64::exp)
+}
+
+// Natural logarithm, or log(x, base)
+fn log(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let values = numbers(interpreter, "log", &args)?;
+    Ok(LiteralValue::Number(match values.get(1) {
+        Some(base) => values[0].log(*base),
+        None => values[0].ln(),
+    }))
+}
+
+fn pow(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    binary(interpreter, "pow", &args, f64::powf)
+}
+
+fn atan2(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    binary(interpreter, "atan2", &args, f64::atan2)
+}
+
+fn hypot(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    binary(interpreter, "hypot", &args, f64::hypot)
+}
+
+// NaN wins, so a bad input is never hidden
+fn min(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let values = numbers(interpreter, "min", &args)?;
+    Ok(LiteralValue::Number(values.into_iter().reduce(|a, b| if a.is_nan() || b.is_nan() { f64::NAN } else { a.min(b) }).unwrap_or(f64::NAN)))
+}
+
+fn max(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let values = numbers(interpreter, "max", &args)?;
+    Ok(LiteralValue::Number(values.into_iter().reduce(|a, b| if a.is_nan() || b.is_nan() { f64::NAN } else { a.max(b) }).unwrap_or(f64::NAN)))
+}
+
+fn is_nan(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Boolean(expect_number(interpreter, "is_nan", &args[0])?.is_nan()))
+}
+
+fn is_finite(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Boolean(expect_number(interpreter, "is_finite", &args[0])?.is_finite()))
+}
@@ -0,0 +1,90 @@
+// Native functions available to every script, grouped by domain.
+// Each module registers its globals in `define_globals` and may expose
+// methods on built-in values through `method_for`.
+pub mod argparse;
+pub mod eval;
+pub mod fs;
+pub mod json;
+pub mod maps;
+pub mod math;
+pub mod process;
+pub mod random;
+pub mod reflect;
+#[cfg(feature = "regex")]
+pub mod regexes;
+pub mod sets;
+pub mod strings;
+pub mod term;
+pub mod time;
+
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::native::{NativeFn, NativeFunction};
+use crate::tool::generate_ast::LiteralValue;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+pub fn define_globals(environment: &mut Environment) {
+    argparse::define_globals(environment);
+    eval::define_globals(environment);
+    fs::define_globals(environment);
+    json::define_globals(environment);
+    math::define_globals(environment);
+    process::define_globals(environment);
+    random::define_globals(environment);
+    reflect::define_globals(environment);
+    #[cfg(feature = "regex")]
+    regexes::define_globals(environment);
+    sets::define_globals(environment);
+    strings::define_globals(environment);
+    term::define_globals(environment);
+    time::define_globals(environment);
+}
+
+// Native method `name` of a built-in value, if it has one
+pub fn method_for(receiver: &LiteralValue, name: &str) -> Option<Rc<NativeFunction>> {
+    match receiver {
+        LiteralValue::Set(_) => sets::method(name),
+        LiteralValue::Map(_) => maps::method(name),
+        LiteralValue::String(_) => strings::method(name),
+        _ => None,
+    }
+    .map(Rc::new)
+}
+
+pub fn native(name: &str, arity: RangeInclusive<usize>, function: NativeFn) -> LiteralValue {
+    LiteralValue::Native(Rc::new(NativeFunction::new(name, arity, function)))
+}
+
+pub fn expect_number(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<f64, Error> {
+    match value {
+        LiteralValue::Number(n) => Ok(*n),
+        other => Err(interpreter.runtime_error(
+            &format!("'{}' expects a number but got {}.", function, other.repr()))),
+    }
+}
+
+// The whole number `n` as an i64, None when it has a fraction or doesn't fit.
+// `as i64` alone would quietly turn 1e20 into i64::MAX.
+pub fn as_integer(n: f64) -> Option<i64> {
+    let fits = n >= i64::MIN as f64 && n < -(i64::MIN as f64);
+    if n.fract() == 0.0 && fits { Some(n as i64) } else { None }
+}
+
+pub fn expect_integer(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<i64, Error> {
+    match value {
+        LiteralValue::Number(n) => as_integer(*n).ok_or_else(|| interpreter.runtime_error(
+            &format!("'{}' expects an integer between -2^63 and 2^63 but got {}.", function, value.repr()))),
+        other => Err(interpreter.runtime_error(
+            &format!("'{}' expects an integer but got {}.", function, other.repr()))),
+    }
+}
+
+pub fn expect_string(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<String, Error> {
+    match value {
+        LiteralValue::String(s) => Ok(s.clone()),
+        other => Err(interpreter.runtime_error(
+            &format!("'{}' expects a string but got {}.", function, other.repr()))),
+    }
+}
@@ -0,0 +1,85 @@
+use crate::lox::builtins::{expect_integer, expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::map::LoxMap;
+use crate::tool::generate_ast::LiteralValue;
+use std::process::Command;
+use std::rc::Rc;
+
+// `args` itself is defined by the lexer once the command line is known
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("args".to_string(), LiteralValue::Tuple(Vec::new()));
+    environment.define_constant("env".to_string(), native("env", 1..=1, env));
+    environment.define_constant("set_env".to_string(), native("set_env", 2..=2, set_env));
+    environment.define_constant("exit".to_string(), native("exit", 0..=1, exit));
+    environment.define_constant("run".to_string(), native("run", 1..=2, run));
+}
+
+// The variable's value, or nil when it is unset or not valid UTF-8
+fn env(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let name = expect_string(interpreter, "env", &args[0])?;
+    Ok(std::env::var(name).map_or(LiteralValue::Nil, LiteralValue::String))
+}
+
+// set_env(name, nil) removes the variable
+fn set_env(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let name = expect_string(interpreter, "set_env", &args[0])?;
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return Err(interpreter.runtime_error(&format!("'set_env' got an invalid variable name {:?}.", name)));
+    }
+    match &args[1] {
+        LiteralValue::Nil => std::env::remove_var(name),
+        value => {
+            let value = expect_string(interpreter, "set_env", value)?;
+            if value.contains('\0') {
+                return Err(interpreter.runtime_error("'set_env' values cannot contain NUL characters."));
+            }
+            std::env::set_var(name, value);
+        },
+    }
+    Ok(LiteralValue::Nil)
+}
+
+// Unwinds the whole program; the lexer turns it into the process exit status
+fn exit(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let code = match args.first() {
+        Some(code) => expect_integer(interpreter, "exit", code)?,
+        None => 0,
+    };
+    match i32::try_from(code) {
+        Ok(code) => Err(Error::Exit(code)),
+        Err(_) => Err(interpreter.runtime_error(&format!("'exit' got an out of range status {}.", code))),
+    }
+}
+
+// run(command, arguments = ()) runs the program directly, without a shell, and waits for it.
+// Returns {"code": .., "stdout": .., "stderr": ..}; code is nil when a signal ended the process.
+fn run(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    if !interpreter.permissions.allow_run {
+        return Err(interpreter.runtime_error("run: Spawning processes is disabled, run with --allow-run to enable it."));
+    }
+    let program = expect_string(interpreter, "run", &args[0])?;
+    let arguments = match args.get(1) {
+        None => Vec::new(),
+        Some(LiteralValue::Tuple(elements)) => elements.iter()
+            .map(|argument| expect_string(interpreter, "run", argument))
+            .collect::<Result<Vec<_>, _>>()?,
+        Some(other) => return Err(interpreter.runtime_error(
+            &format!("'run' expects a tuple of arguments but got {}.", other.repr()))),
+    };
+    let output = Command::new(&program).args(&arguments).output()
+        .map_err(|e| interpreter.runtime_error(&format!("run: '{}': {}", program, e)))?;
+
+    let result = LoxMap::new();
+    let code = output.status.code().map_or(LiteralValue::Nil, |code| LiteralValue::Number(code as f64));
+    let fields = [
+        ("code", code),
+        ("stdout", LiteralValue::String(String::from_utf8_lossy(&output.stdout).into_owned())),
+        ("stderr", LiteralValue::String(String::from_utf8_lossy(&output.stderr).into_owned())),
+    ];
+    for (name, value) in fields {
+        let _ = result.insert(LiteralValue::String(name.to_string()), value);
+    }
+    Ok(LiteralValue::Map(Rc::new(result)))
+}
@@ -0,0 +1,69 @@
+use crate::lox::builtins::{expect_integer, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::rng::Rng;
+use crate::tool::generate_ast::LiteralValue;
+
+// Pseudo-random numbers from `Interpreter::rng`. Not suitable for secrets.
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("random".to_string(), native("random", 0..=0, random));
+    environment.define_constant("random_int".to_string(), native("random_int", 2..=2, random_int));
+    environment.define_constant("choice".to_string(), native("choice", 1..=1, choice));
+    environment.define_constant("shuffle".to_string(), native("shuffle", 1..=1, shuffle));
+    environment.define_constant("seed".to_string(), native("seed", 1..=1, seed));
+}
+
+// Elements of a tuple, or the characters of a string
+fn sequence(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<Vec<LiteralValue>, Error> {
+    match value {
+        LiteralValue::Tuple(elements) => Ok(elements.clone()),
+        LiteralValue::String(s) => Ok(s.chars().map(|c| LiteralValue::String(c.to_string())).collect()),
+        other => Err(interpreter.runtime_error(
+            &format!("'{}' expects a tuple or a string but got {}.", function, other.repr()))),
+    }
+}
+
+// Uniform in [0, 1)
+fn random(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Number(interpreter.rng.next_f64()))
+}
+
+// Uniform integer with both bounds included
+fn random_int(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let lo = expect_integer(interpreter, "random_int", &args[0])?;
+    let hi = expect_integer(interpreter, "random_int", &args[1])?;
+    if lo > hi {
+        return Err(interpreter.runtime_error(
+            &format!("'random_int' expects lo <= hi, got {} and {}.", lo, hi)));
+    }
+    Ok(LiteralValue::Number(interpreter.rng.range_inclusive(lo, hi) as f64))
+}
+
+fn choice(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let mut elements = sequence(interpreter, "choice", &args[0])?;
+    if elements.is_empty() {
+        return Err(interpreter.runtime_error("'choice' cannot pick from an empty sequence."));
+    }
+    let index = interpreter.rng.below(elements.len() as u64) as usize;
+    Ok(elements.swap_remove(index))
+}
+
+// Tuples are immutable, so this returns a shuffled copy
+fn shuffle(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let mut elements = sequence(interpreter, "shuffle", &args[0])?;
+    interpreter.rng.shuffle(&mut elements);
+    match &args[0] {
+        LiteralValue::String(_) => Ok(LiteralValue::String(elements.iter().map(|c| match c {
+            LiteralValue::String(c) => c.as_str(),
+            _ => "",
+        }).collect())),
+        _ => Ok(LiteralValue::Tuple(elements)),
+    }
+}
+
+fn seed(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let seed = expect_integer(interpreter, "seed", &args[0])?;
+    interpreter.rng = Rng::seeded(seed as u64);
+    Ok(LiteralValue::Nil)
+}
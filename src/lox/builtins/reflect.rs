@@ -0,0 +1,107 @@
+use crate::lox::builtins::{expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::map::LoxMap;
+use crate::tool::generate_ast::LiteralValue;
+use std::rc::Rc;
+
+// Introspection for helpers written in Lox: type names, the variables in
+// scope and the fields of records and variants.
+
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("type".to_string(), native("type", 1..=1, type_of));
+    environment.define_constant("globals".to_string(), native("globals", 0..=0, globals));
+    environment.define_constant("locals".to_string(), native("locals", 0..=0, locals));
+    environment.define_constant("defined".to_string(), native("defined", 1..=1, defined));
+    environment.define_constant("fields".to_string(), native("fields", 1..=1, fields));
+}
+
+// The names match the type checker's: type(1) == "Number", type(Point(1, 2)) == "Point"
+fn type_of(_interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let name = match &args[0] {
+        LiteralValue::Number(_) => "Number".to_string(),
+        LiteralValue::String(_) => "String".to_string(),
+        LiteralValue::Boolean(_) => "Boolean".to_string(),
+        LiteralValue::Nil => "Nil".to_string(),
+        LiteralValue::Tuple(_) => "Tuple".to_string(),
+        LiteralValue::Set(_) => "Set".to_string(),
+        LiteralValue::Map(_) => "Map".to_string(),
+        LiteralValue::Namespace(_) => "Namespace".to_string(),
+        LiteralValue::Function(_) | LiteralValue::Native(_) | LiteralValue::BoundMethod(_)
+        | LiteralValue::VariantConstructor(_) => "Function".to_string(),
+        LiteralValue::RecordType(record_type) => format!("record {}", record_type.name),
+        LiteralValue::Enum(enum_type) => format!("enum {}", enum_type.name),
+        LiteralValue::Record(record) => record.record_type.name.clone(),
+        LiteralValue::Variant(variant) => variant.variant.enum_name.clone(),
+    };
+    Ok(LiteralValue::String(name))
+}
+
+fn to_map(bindings: impl IntoIterator<Item = (String, LiteralValue)>) -> LiteralValue {
+    let map = LoxMap::new();
+    for (name, value) in bindings {
+        let _ = map.insert(LiteralValue::String(name), value);
+    }
+    LiteralValue::Map(Rc::new(map))
+}
+
+// Every global binding, built-ins included
+fn globals(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let mut environment = interpreter.environment();
+    loop {
+        let enclosing = environment.borrow().enclosing.clone();
+        match enclosing {
+            Some(enclosing) => environment = enclosing,
+            None => break,
+        }
+    }
+    let bindings = environment.borrow().bindings();
+    Ok(to_map(bindings))
+}
+
+// The bindings between the caller and the globals, inner scopes shadowing outer ones.
+// Empty at the top level of a script.
+fn locals(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let mut scopes = Vec::new();
+    let mut environment = interpreter.environment();
+    loop {
+        let enclosing = environment.borrow().enclosing.clone();
+        match enclosing {
+            Some(enclosing) => {
+                scopes.push(environment.borrow().bindings());
+                environment = enclosing;
+            },
+            None => break,
+        }
+    }
+    let map = LoxMap::new();
+    for (name, value) in scopes.into_iter().flatten() {
+        let key = LiteralValue::String(name);
+        if !map.contains_key(&key) {
+            let _ = map.insert(key, value);
+        }
+    }
+    Ok(LiteralValue::Map(Rc::new(map)))
+}
+
+fn defined(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let name = expect_string(interpreter, "defined", &args[0])?;
+    let defined = interpreter.environment().borrow().is_defined(&name);
+    Ok(LiteralValue::Boolean(defined))
+}
+
+// Field names in declaration order; variant names for an enum, member names for a namespace
+fn fields(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let names: Vec<String> = match &args[0] {
+        LiteralValue::Record(record) => record.record_type.fields.clone(),
+        LiteralValue::RecordType(record_type) => record_type.fields.clone(),
+        LiteralValue::Variant(variant) => variant.variant.fields.clone(),
+        LiteralValue::VariantConstructor(variant) => variant.fields.clone(),
+        LiteralValue::Enum(enum_type) => enum_type.variants.iter().map(|variant| variant.name.clone()).collect(),
+        LiteralValue::Namespace(namespace) => namespace.members.keys().cloned().collect(),
+        other => return Err(interpreter.runtime_error(
+            &format!("'fields' expects a record, variant, enum or namespace but got {}.", other.repr()))),
+    };
+    Ok(LiteralValue::Tuple(names.into_iter().map(LiteralValue::String).collect()))
+}
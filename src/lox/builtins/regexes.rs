@@ -0,0 +1,116 @@
+use crate::lox::builtins::{expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::tool::generate_ast::LiteralValue;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Regular expressions backed by the `regex` crate, whose matching time is
+// linear in the input, so a hostile pattern can't hang a script.
+// Matches are searched anywhere in the text; anchor with ^ and $ as needed.
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("regex_match".to_string(), native("regex_match", 2..=2, regex_match));
+    environment.define_constant("regex_find_all".to_string(), native("regex_find_all", 2..=2, regex_find_all));
+    environment.define_constant("regex_replace".to_string(), native("regex_replace", 3..=3, regex_replace));
+    environment.define_constant("regex_split".to_string(), native("regex_split", 2..=2, regex_split));
+}
+
+// Compiled patterns, kept for the lifetime of the interpreter.
+// Cleared when full so scripts building patterns on the fly can't grow it forever.
+#[derive(Default)]
+pub struct RegexCache {
+    patterns: HashMap<String, Rc<Regex>>,
+}
+
+const CACHE_CAPACITY: usize = 256;
+
+impl RegexCache {
+    pub fn new() -> Self {
+        RegexCache::default()
+    }
+
+    fn get(&mut self, pattern: &str) -> Result<Rc<Regex>, regex::Error> {
+        if let Some(regex) = self.patterns.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Rc::new(Regex::new(pattern)?);
+        if self.patterns.len() >= CACHE_CAPACITY {
+            self.patterns.clear();
+        }
+        self.patterns.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    }
+}
+
+fn compile(interpreter: &mut Interpreter, function: &str, value: &LiteralValue) -> Result<Rc<Regex>, Error> {
+    let pattern = expect_string(interpreter, function, value)?;
+    match interpreter.regex_cache.get(&pattern) {
+        Ok(regex) => Ok(regex),
+        Err(e) => Err(interpreter.runtime_error(&format!("{}: invalid pattern: {}", function, e))),
+    }
+}
+
+// The whole match followed by every group, nil for groups that did not take part
+fn captures_tuple(captures: &Captures) -> LiteralValue {
+    LiteralValue::Tuple(captures.iter()
+        .map(|group| group.map_or(LiteralValue::Nil, |m| LiteralValue::String(m.as_str().to_string())))
+        .collect())
+}
+
+// regex_match(pattern, text) is nil without a match, otherwise (match, group1, ...)
+fn regex_match(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let regex = compile(interpreter, "regex_match", &args[0])?;
+    let text = expect_string(interpreter, "regex_match", &args[1])?;
+    Ok(regex.captures(&text).map_or(LiteralValue::Nil, |captures| captures_tuple(&captures)))
+}
+
+// Strings when the pattern has no groups, (match, group1, ...) tuples when it does
+fn regex_find_all(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let regex = compile(interpreter, "regex_find_all", &args[0])?;
+    let text = expect_string(interpreter, "regex_find_all", &args[1])?;
+    let matches = if regex.captures_len() > 1 {
+        regex.captures_iter(&text).map(|captures| captures_tuple(&captures)).collect()
+    } else {
+        regex.find_iter(&text).map(|m| LiteralValue::String(m.as_str().to_string())).collect()
+    };
+    Ok(LiteralValue::Tuple(matches))
+}
+
+// Replaces every match; the replacement may refer to groups as $1 or ${name}
+fn regex_replace(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let regex = compile(interpreter, "regex_replace", &args[0])?;
+    let text = expect_string(interpreter, "regex_replace", &args[1])?;
+    let replacement = expect_string(interpreter, "regex_replace", &args[2])?;
+    Ok(LiteralValue::String(regex.replace_all(&text, replacement.as_str()).into_owned()))
+}
+
+fn regex_split(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let regex = compile(interpreter, "regex_split", &args[0])?;
+    let text = expect_string(interpreter, "regex_split", &args[1])?;
+    Ok(LiteralValue::Tuple(regex.split(&text).map(|part| LiteralValue::String(part.to_string())).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_are_compiled_once() {
+        let mut cache = RegexCache::new();
+        let first = cache.get("a+").unwrap();
+        assert!(Rc::ptr_eq(&first, &cache.get("a+").unwrap()));
+        assert!(cache.get("(").is_err());
+    }
+
+    #[test]
+    fn a_full_cache_starts_over() {
+        let mut cache = RegexCache::new();
+        for n in 0..CACHE_CAPACITY {
+            cache.get(&n.to_string()).unwrap();
+        }
+        cache.get("one more").unwrap();
+        assert_eq!(cache.patterns.len(), 1);
+    }
+}
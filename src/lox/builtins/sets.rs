@@ -0,0 +1,104 @@
+use crate::lox::builtins::native;
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::native::{NativeFn, NativeFunction};
+use crate::lox::set::LoxSet;
+use crate::tool::generate_ast::LiteralValue;
+use std::rc::Rc;
+
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("Set".to_string(), native("Set", 0..=usize::MAX, set_constructor));
+}
+
+pub fn method(name: &str) -> Option<NativeFunction> {
+    let (arity, function): (_, NativeFn) = match name {
+        "contains" => (1..=1, contains),
+        "add" => (1..=1, add),
+        "remove" => (1..=1, remove),
+        "len" => (0..=0, len),
+        "union" => (1..=1, union),
+        "intersection" => (1..=1, intersection),
+        "difference" => (1..=1, difference),
+        "is_subset" => (1..=1, is_subset),
+        "to_tuple" => (0..=0, to_tuple),
+        _ => return None,
+    };
+    Some(NativeFunction::new(name, arity, function))
+}
+
+// Builds a set from any number of hashable values
+pub fn build_set(interpreter: &Interpreter, values: impl IntoIterator<Item = LiteralValue>) -> Result<LoxSet, Error> {
+    let mut set = LoxSet::new();
+    for value in values {
+        if let Err(value) = set.insert(value) {
+            return Err(interpreter.runtime_error(
+                &format!("Set elements must be nil, booleans, numbers, strings or tuples of those, got {}.", value.repr())));
+        }
+    }
+    Ok(set)
+}
+
+fn expect_set(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<Rc<LoxSet>, Error> {
+    match value {
+        LiteralValue::Set(set) => Ok(set.clone()),
+        other => Err(interpreter.runtime_error(
+            &format!("'{}' expects a set but got {}.", function, other.repr()))),
+    }
+}
+
+// Methods receive the set itself as their first argument
+fn receiver_and_other(interpreter: &Interpreter, function: &str, args: &[LiteralValue]) -> Result<(Rc<LoxSet>, Rc<LoxSet>), Error> {
+    Ok((expect_set(interpreter, function, &args[0])?, expect_set(interpreter, function, &args[1])?))
+}
+
+fn set_constructor(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Set(Rc::new(build_set(interpreter, args)?)))
+}
+
+fn contains(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let set = expect_set(interpreter, "contains", &args[0])?;
+    Ok(LiteralValue::Boolean(set.contains(&args[1])))
+}
+
+fn add(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let set = expect_set(interpreter, "add", &args[0])?;
+    let added = build_set(interpreter, set.items().iter().cloned().chain([args[1].clone()]))?;
+    Ok(LiteralValue::Set(Rc::new(added)))
+}
+
+fn remove(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let set = expect_set(interpreter, "remove", &args[0])?;
+    let removed = build_set(interpreter, [args[1].clone()])?;
+    Ok(LiteralValue::Set(Rc::new(set.difference(&removed))))
+}
+
+fn len(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let set = expect_set(interpreter, "len", &args[0])?;
+    Ok(LiteralValue::Number(set.len() as f64))
+}
+
+fn union(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (set, other) = receiver_and_other(interpreter, "union", &args)?;
+    Ok(LiteralValue::Set(Rc::new(set.union(&other))))
+}
+
+fn intersection(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (set, other) = receiver_and_other(interpreter, "intersection", &args)?;
+    Ok(LiteralValue::Set(Rc::new(set.intersection(&other))))
+}
+
+fn difference(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (set, other) = receiver_and_other(interpreter, "difference", &args)?;
+    Ok(LiteralValue::Set(Rc::new(set.difference(&other))))
+}
+
+fn is_subset(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (set, other) = receiver_and_other(interpreter, "is_subset", &args)?;
+    Ok(LiteralValue::Boolean(set.is_subset(&other)))
+}
+
+fn to_tuple(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let set = expect_set(interpreter, "to_tuple", &args[0])?;
+    Ok(LiteralValue::Tuple(set.items().to_vec()))
+}
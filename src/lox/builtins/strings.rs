@@ -0,0 +1,206 @@
+use crate::lox::builtins::{expect_integer, expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::format;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::native::{NativeFn, NativeFunction};
+use crate::tool::generate_ast::LiteralValue;
+use std::rc::Rc;
+
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("str".to_string(), native("str", 1..=1, str));
+    let format = NativeFunction::new("format", 1..=usize::MAX, format).with_named_arguments();
+    environment.define_constant("format".to_string(), LiteralValue::Native(Rc::new(format)));
+}
+
+// The text `print` would show for the value
+fn str(_interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::String(args[0].to_string()))
+}
+
+// format("{:>8.2} | {name}", x, name: y); named arguments arrive as a trailing map
+fn format(interpreter: &mut Interpreter, mut args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let named = match args.pop() {
+        Some(LiteralValue::Map(map)) => map.entries().into_iter()
+            .filter_map(|(key, value)| match key {
+                LiteralValue::String(name) => Some((name, value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+    let pattern = expect_string(interpreter, "format", &args[0])?;
+    let template = format::parse(&pattern)
+        .map_err(|e| interpreter.runtime_error(&format!("format: {}.", e)))?;
+    template.render(&args[1..], &named)
+        .map(LiteralValue::String)
+        .map_err(|e| interpreter.runtime_error(&format!("format: {}.", e)))
+}
+
+// Methods of string values: "a,b".split(",").
+// Every method receives the string itself as its first argument.
+pub fn method(name: &str) -> Option<NativeFunction> {
+    let (arity, function): (_, NativeFn) = match name {
+        "len" => (0..=0, len),
+        "upper" => (0..=0, upper),
+        "lower" => (0..=0, lower),
+        "trim" => (0..=0, trim),
+        "split" => (0..=1, split),
+        "join" => (1..=1, join),
+        "replace" => (2..=2, replace),
+        "find" => (1..=1, find),
+        "starts_with" => (1..=1, starts_with),
+        "ends_with" => (1..=1, ends_with),
+        "pad_left" => (1..=2, pad_left),
+        "repeat" => (1..=1, repeat),
+        "chars" => (0..=0, chars),
+        "lines" => (0..=0, lines),
+        "to_number" => (0..=0, to_number),
+        _ => return None,
+    };
+    Some(NativeFunction::new(name, arity, function))
+}
+
+// Longest string, in bytes, that pad_left and repeat will build
+const MAX_LENGTH: usize = 1 << 24;
+
+// `extra` bytes appended to `base` bytes, if the total stays within MAX_LENGTH
+fn checked_length(base: usize, extra: Option<usize>) -> Option<usize> {
+    extra.and_then(|extra| base.checked_add(extra)).filter(|&total| total <= MAX_LENGTH)
+}
+
+fn strings(parts: impl Iterator<Item = impl Into<String>>) -> LiteralValue {
+    LiteralValue::Tuple(parts.map(|part| LiteralValue::String(part.into())).collect())
+}
+
+fn len(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "len", &args[0])?;
+    Ok(LiteralValue::Number(s.chars().count() as f64))
+}
+
+fn upper(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::String(expect_string(interpreter, "upper", &args[0])?.to_uppercase()))
+}
+
+fn lower(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::String(expect_string(interpreter, "lower", &args[0])?.to_lowercase()))
+}
+
+fn trim(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::String(expect_string(interpreter, "trim", &args[0])?.trim().to_string()))
+}
+
+// Without a separator, splits on runs of whitespace
+fn split(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "split", &args[0])?;
+    let Some(separator) = args.get(1) else {
+        return Ok(strings(s.split_whitespace()));
+    };
+    let separator = expect_string(interpreter, "split", separator)?;
+    if separator.is_empty() {
+        return Err(interpreter.runtime_error("'split' separator cannot be empty, use 'chars' instead."));
+    }
+    Ok(strings(s.split(separator.as_str())))
+}
+
+// The receiver is the separator: ", ".join(("a", "b"))
+fn join(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let separator = expect_string(interpreter, "join", &args[0])?;
+    let items = match &args[1] {
+        LiteralValue::Tuple(items) => items.clone(),
+        LiteralValue::Set(set) => set.items().to_vec(),
+        other => return Err(interpreter.runtime_error(
+            &format!("'join' expects a tuple or a set of strings but got {}.", other.repr()))),
+    };
+    let mut parts = Vec::with_capacity(items.len());
+    for item in &items {
+        parts.push(expect_string(interpreter, "join", item)?);
+    }
+    Ok(LiteralValue::String(parts.join(&separator)))
+}
+
+fn replace(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "replace", &args[0])?;
+    let from = expect_string(interpreter, "replace", &args[1])?;
+    let to = expect_string(interpreter, "replace", &args[2])?;
+    if from.is_empty() {
+        return Err(interpreter.runtime_error("'replace' pattern cannot be empty."));
+    }
+    Ok(LiteralValue::String(s.replace(&from, &to)))
+}
+
+// Position of the first match counted in characters, or -1
+fn find(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "find", &args[0])?;
+    let needle = expect_string(interpreter, "find", &args[1])?;
+    let position = match s.find(&needle) {
+        Some(byte_offset) => s[..byte_offset].chars().count() as f64,
+        None => -1.0,
+    };
+    Ok(LiteralValue::Number(position))
+}
+
+fn starts_with(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "starts_with", &args[0])?;
+    let prefix = expect_string(interpreter, "starts_with", &args[1])?;
+    Ok(LiteralValue::Boolean(s.starts_with(&prefix)))
+}
+
+fn ends_with(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "ends_with", &args[0])?;
+    let suffix = expect_string(interpreter, "ends_with", &args[1])?;
+    Ok(LiteralValue::Boolean(s.ends_with(&suffix)))
+}
+
+// pad_left(width, fill = " ")
+fn pad_left(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "pad_left", &args[0])?;
+    let width = expect_integer(interpreter, "pad_left", &args[1])?;
+    let fill = match args.get(2) {
+        Some(fill) => expect_string(interpreter, "pad_left", fill)?,
+        None => " ".to_string(),
+    };
+    let mut fill_chars = fill.chars();
+    let (Some(fill), None) = (fill_chars.next(), fill_chars.next()) else {
+        return Err(interpreter.runtime_error("'pad_left' fill must be a single character."));
+    };
+    let missing = usize::try_from(width.max(0)).unwrap_or(usize::MAX).saturating_sub(s.chars().count());
+    if checked_length(s.len(), missing.checked_mul(fill.len_utf8())).is_none() {
+        return Err(interpreter.runtime_error(
+            &format!("'pad_left' result would be longer than {} bytes.", MAX_LENGTH)));
+    }
+    Ok(LiteralValue::String(std::iter::repeat_n(fill, missing).chain(s.chars()).collect()))
+}
+
+fn repeat(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "repeat", &args[0])?;
+    let count = expect_integer(interpreter, "repeat", &args[1])?;
+    if count < 0 {
+        return Err(interpreter.runtime_error(&format!("'repeat' count cannot be negative, got {}.", count)));
+    }
+    let count = usize::try_from(count).unwrap_or(usize::MAX);
+    if checked_length(0, s.len().checked_mul(count)).is_none() {
+        return Err(interpreter.runtime_error(
+            &format!("'repeat' result would be longer than {} bytes.", MAX_LENGTH)));
+    }
+    Ok(LiteralValue::String(s.repeat(count)))
+}
+
+fn chars(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "chars", &args[0])?;
+    Ok(strings(s.chars().map(String::from)))
+}
+
+fn lines(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "lines", &args[0])?;
+    Ok(strings(s.lines()))
+}
+
+// nil when the text is not a number
+fn to_number(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let s = expect_string(interpreter, "to_number", &args[0])?;
+    Ok(match s.trim().parse::<f64>() {
+        Ok(n) => LiteralValue::Number(n),
+        Err(_) => LiteralValue::Nil,
+    })
+}
@@ -0,0 +1,216 @@
+use crate::lox::builtins::{expect_integer, expect_string, native};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::map::LoxMap;
+use crate::lox::namespace::Namespace;
+use crate::lox::terminal::{self, RawMode};
+use crate::tool::generate_ast::LiteralValue;
+use std::io::Write;
+use std::rc::Rc;
+
+// Terminal control under the `term` namespace. When stdout is not a TTY the
+// colour helpers return their text unchanged and cursor and screen control
+// writes nothing, so the same script can also feed a pipe.
+pub fn define_globals(environment: &mut Environment) {
+    let mut term = Namespace::new("term");
+    term.define("is_tty", native("is_tty", 0..=0, is_tty));
+    term.define("color", native("color", 2..=2, color));
+    term.define("background", native("background", 2..=2, background));
+    term.define("style", native("style", 2..=usize::MAX, style));
+    term.define("move_to", native("move_to", 2..=2, move_to));
+    term.define("up", native("up", 0..=1, up));
+    term.define("down", native("down", 0..=1, down));
+    term.define("left", native("left", 0..=1, left));
+    term.define("right", native("right", 0..=1, right));
+    term.define("clear", native("clear", 0..=0, clear));
+    term.define("clear_line", native("clear_line", 0..=0, clear_line));
+    term.define("hide_cursor", native("hide_cursor", 0..=0, hide_cursor));
+    term.define("show_cursor", native("show_cursor", 0..=0, show_cursor));
+    term.define("size", native("size", 0..=0, size));
+    term.define("raw_mode", native("raw_mode", 1..=1, raw_mode));
+    term.define("read_key", native("read_key", 0..=0, read_key));
+    environment.define_constant("term".to_string(), LiteralValue::Namespace(Rc::new(term)));
+}
+
+fn is_tty(_interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Boolean(terminal::stdout_is_tty()))
+}
+
+// Writes a control sequence, or nothing when stdout is redirected
+fn emit(interpreter: &Interpreter, function: &str, sequence: &str) -> Result<LiteralValue, Error> {
+    if terminal::stdout_is_tty() {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(sequence.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| interpreter.runtime_error(&format!("term.{}: {}", function, e)))?;
+    }
+    Ok(LiteralValue::Nil)
+}
+
+// Foreground SGR code of a colour name, or of a 0-255 palette index
+fn color_code(interpreter: &Interpreter, function: &str, color: &LiteralValue) -> Result<String, Error> {
+    const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    if let LiteralValue::Number(_) = color {
+        let index = expect_integer(interpreter, function, color)?;
+        if !(0..=255).contains(&index) {
+            return Err(interpreter.runtime_error(&format!("term.{}: palette index {} is not in 0..255.", function, index)));
+        }
+        return Ok(format!("38;5;{}", index));
+    }
+    let name = expect_string(interpreter, function, color)?;
+    let (bright, base) = match name.strip_prefix("bright_") {
+        Some(base) => (true, base),
+        None => (false, name.as_str()),
+    };
+    match NAMES.iter().position(|candidate| *candidate == base) {
+        Some(index) => Ok((if bright { 90 } else { 30 } + index).to_string()),
+        None => Err(interpreter.runtime_error(&format!(
+            "term.{}: unknown colour '{}', expected one of {} (optionally bright_) or 0..255.", function, name, NAMES.join(", ")))),
+    }
+}
+
+fn paint(text: &str, codes: &[String]) -> LiteralValue {
+    if !terminal::colors_enabled() || codes.is_empty() {
+        return LiteralValue::String(text.to_string());
+    }
+    LiteralValue::String(format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text))
+}
+
+// term.color("error", "red"), term.color("x", "bright_cyan"), term.color("x", 208)
+fn color(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let text = args[0].to_string();
+    let code = color_code(interpreter, "color", &args[1])?;
+    Ok(paint(&text, &[code]))
+}
+
+fn background(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let text = args[0].to_string();
+    // Background codes are the foreground ones plus 10
+    let code = color_code(interpreter, "background", &args[1])?;
+    let code = match code.strip_prefix("38;") {
+        Some(palette) => format!("48;{}", palette),
+        None => (code.parse::<u32>().unwrap_or(39) + 10).to_string(),
+    };
+    Ok(paint(&text, &[code]))
+}
+
+// term.style("title", "bold", "underline")
+fn style(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let text = args[0].to_string();
+    let mut codes = Vec::new();
+    for style in &args[1..] {
+        let name = expect_string(interpreter, "style", style)?;
+        let code = match name.as_str() {
+            "bold" => 1,
+            "dim" => 2,
+            "italic" => 3,
+            "underline" => 4,
+            "blink" => 5,
+            "inverse" => 7,
+            "hidden" => 8,
+            "strikethrough" => 9,
+            _ => return Err(interpreter.runtime_error(&format!(
+                "term.style: unknown style '{}', expected bold, dim, italic, underline, blink, inverse, hidden or strikethrough.", name))),
+        };
+        codes.push(code.to_string());
+    }
+    Ok(paint(&text, &codes))
+}
+
+fn count(interpreter: &Interpreter, function: &str, args: &[LiteralValue], minimum: i64) -> Result<i64, Error> {
+    let value = match args.first() {
+        Some(value) => expect_integer(interpreter, function, value)?,
+        None => 1,
+    };
+    if value < minimum {
+        return Err(interpreter.runtime_error(&format!("term.{}: expected a number of at least {} but got {}.", function, minimum, value)));
+    }
+    Ok(value)
+}
+
+// Rows and columns start at 1, like the terminal's own numbering
+fn move_to(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let row = count(interpreter, "move_to", &args[0..1], 1)?;
+    let column = count(interpreter, "move_to", &args[1..2], 1)?;
+    emit(interpreter, "move_to", &format!("\x1b[{};{}H", row, column))
+}
+
+fn up(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let n = count(interpreter, "up", &args, 0)?;
+    emit(interpreter, "up", &format!("\x1b[{}A", n))
+}
+
+fn down(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let n = count(interpreter, "down", &args, 0)?;
+    emit(interpreter, "down", &format!("\x1b[{}B", n))
+}
+
+fn right(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let n = count(interpreter, "right", &args, 0)?;
+    emit(interpreter, "right", &format!("\x1b[{}C", n))
+}
+
+fn left(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let n = count(interpreter, "left", &args, 0)?;
+    emit(interpreter, "left", &format!("\x1b[{}D", n))
+}
+
+// Clears the screen and puts the cursor in the top left corner
+fn clear(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    emit(interpreter, "clear", "\x1b[2J\x1b[H")
+}
+
+fn clear_line(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    emit(interpreter, "clear_line", "\x1b[2K\r")
+}
+
+// A hidden cursor is shown again when the script ends
+fn hide_cursor(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    interpreter.terminal.cursor_hidden = terminal::stdout_is_tty();
+    emit(interpreter, "hide_cursor", "\x1b[?25l")
+}
+
+fn show_cursor(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    interpreter.terminal.cursor_hidden = false;
+    emit(interpreter, "show_cursor", "\x1b[?25h")
+}
+
+// {"columns": 80, "rows": 24}
+fn size(_interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let (columns, rows) = terminal::size();
+    let map = LoxMap::new();
+    let _ = map.insert(LiteralValue::String("columns".to_string()), LiteralValue::Number(columns as f64));
+    let _ = map.insert(LiteralValue::String("rows".to_string()), LiteralValue::Number(rows as f64));
+    Ok(LiteralValue::Map(Rc::new(map)))
+}
+
+// term.raw_mode(true) keeps keys unbuffered and unechoed until term.raw_mode(false)
+// or the end of the script, whichever comes first
+fn raw_mode(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let LiteralValue::Boolean(enable) = args[0] else {
+        return Err(interpreter.runtime_error(&format!("term.raw_mode expects true or false but got {}.", args[0].repr())));
+    };
+    if !enable {
+        interpreter.terminal.raw_mode = None;
+    } else if interpreter.terminal.raw_mode.is_none() {
+        let mode = RawMode::enable()
+            .map_err(|e| interpreter.runtime_error(&format!("term.raw_mode: no terminal to switch ({}).", e)))?;
+        interpreter.terminal.raw_mode = Some(mode);
+    }
+    Ok(LiteralValue::Nil)
+}
+
+// The next key press as a name ("a", "enter", "up", "ctrl-c", ...), nil at end of input.
+// Outside of raw mode, raw mode is switched on just for this key when a terminal is available.
+fn read_key(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let _temporary = match interpreter.terminal.raw_mode {
+        Some(_) => None,
+        None => RawMode::enable().ok(),
+    };
+    match terminal::read_key() {
+        Ok(Some(key)) => Ok(LiteralValue::String(key)),
+        Ok(None) => Ok(LiteralValue::Nil),
+        Err(e) => Err(interpreter.runtime_error(&format!("term.read_key: {}", e))),
+    }
+}
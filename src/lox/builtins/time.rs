@@ -0,0 +1,235 @@
+use crate::lox::builtins::{expect_integer, expect_number, expect_string, native};
+use crate::lox::date::{self, DateTime};
+use crate::lox::environment::Environment;
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::lox::map::LoxMap;
+use crate::lox::namespace::Namespace;
+use crate::tool::generate_ast::LiteralValue;
+use std::rc::Rc;
+use std::time::Duration;
+
+// Time goes through `Interpreter::clock`, never straight to the system.
+// Timestamps and durations are plain numbers of seconds, so `now() + duration.days(1)`
+// and `b - a` are ordinary arithmetic.
+pub fn define_globals(environment: &mut Environment) {
+    environment.define_constant("clock".to_string(), native("clock", 0..=0, clock));
+    environment.define_constant("now".to_string(), native("now", 0..=0, now));
+    environment.define_constant("sleep".to_string(), native("sleep", 1..=1, sleep));
+    environment.define_constant("date".to_string(), native("date", 3..=6, date));
+    environment.define_constant("date_parts".to_string(), native("date_parts", 1..=1, date_parts));
+    environment.define_constant("date_format".to_string(), native("date_format", 1..=2, date_format));
+    environment.define_constant("date_parse".to_string(), native("date_parse", 2..=2, date_parse));
+
+    let mut duration = Namespace::new("duration");
+    duration.define("milliseconds", native("milliseconds", 1..=1, milliseconds));
+    duration.define("seconds", native("seconds", 1..=1, seconds));
+    duration.define("minutes", native("minutes", 1..=1, minutes));
+    duration.define("hours", native("hours", 1..=1, hours));
+    duration.define("days", native("days", 1..=1, days));
+    duration.define("weeks", native("weeks", 1..=1, weeks));
+    environment.define_constant("duration".to_string(), LiteralValue::Namespace(Rc::new(duration)));
+}
+
+const DEFAULT_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+// Monotonic seconds, for measuring elapsed time
+fn clock(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Number(interpreter.clock.monotonic()))
+}
+
+// Current UTC timestamp in seconds
+fn now(interpreter: &mut Interpreter, _args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Number(interpreter.clock.now()))
+}
+
+fn sleep(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let milliseconds = expect_number(interpreter, "sleep", &args[0])?;
+    // Rejects negative, NaN, infinite and too large values alike
+    let Ok(duration) = Duration::try_from_secs_f64(milliseconds / 1000.0) else {
+        return Err(interpreter.runtime_error(
+            &format!("'sleep' expects a non-negative, finite number of milliseconds, got {}.", milliseconds)));
+    };
+    interpreter.clock.sleep(duration);
+    Ok(LiteralValue::Nil)
+}
+
+// date(year, month, day, hour = 0, minute = 0, second = 0) as a UTC timestamp
+fn date(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let mut fields = [0i64; 6];
+    for (field, value) in fields.iter_mut().zip(&args) {
+        *field = expect_integer(interpreter, "date", value)?;
+    }
+    let [year, month, day, hour, minute, second] = fields;
+    // Negative or huge fields become u32::MAX, which `DateTime::new` rejects
+    let field = |value: i64| u32::try_from(value).unwrap_or(u32::MAX);
+    match DateTime::new(year, field(month), field(day), field(hour), field(minute), field(second)) {
+        Ok(date) => Ok(LiteralValue::Number(date.timestamp())),
+        Err(message) => Err(interpreter.runtime_error(&format!("'date' got an invalid date: {}.", message))),
+    }
+}
+
+// {"year": .., "month": .., "day": .., "hour": .., "minute": .., "second": .., "weekday": ..}, weekday 0 is Sunday
+fn date_parts(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let timestamp = expect_timestamp(interpreter, "date_parts", &args[0])?;
+    let date = DateTime::from_timestamp(timestamp)
+        .map_err(|message| interpreter.runtime_error(&format!("date_parts: {}.", message)))?;
+    let parts = LoxMap::new();
+    let fields = [
+        ("year", date.year as f64), ("month", date.month as f64), ("day", date.day as f64),
+        ("hour", date.hour as f64), ("minute", date.minute as f64), ("second", date.second as f64),
+        ("weekday", date.weekday() as f64),
+    ];
+    for (name, value) in fields {
+        let _ = parts.insert(LiteralValue::String(name.to_string()), LiteralValue::Number(value));
+    }
+    Ok(LiteralValue::Map(Rc::new(parts)))
+}
+
+// date_format(timestamp, pattern = "%Y-%m-%dT%H:%M:%SZ")
+fn date_format(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let timestamp = expect_timestamp(interpreter, "date_format", &args[0])?;
+    let pattern = match args.get(1) {
+        Some(pattern) => expect_string(interpreter, "date_format", pattern)?,
+        None => DEFAULT_FORMAT.to_string(),
+    };
+    date::format(timestamp, &pattern)
+        .map(LiteralValue::String)
+        .map_err(|message| interpreter.runtime_error(&format!("date_format: {}.", message)))
+}
+
+fn date_parse(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    let text = expect_string(interpreter, "date_parse", &args[0])?;
+    let pattern = expect_string(interpreter, "date_parse", &args[1])?;
+    date::parse(&text, &pattern)
+        .map(LiteralValue::Number)
+        .map_err(|message| interpreter.runtime_error(&format!("date_parse: {} in '{}'.", message, text)))
+}
+
+fn expect_timestamp(interpreter: &Interpreter, function: &str, value: &LiteralValue) -> Result<f64, Error> {
+    let timestamp = expect_number(interpreter, function, value)?;
+    if !date::timestamp_in_range(timestamp) {
+        return Err(interpreter.runtime_error(&format!(
+            "'{}' expects a timestamp within the years {}..{}, got {}.", function, date::MIN_YEAR, date::MAX_YEAR, timestamp)));
+    }
+    Ok(timestamp)
+}
+
+fn scaled(interpreter: &Interpreter, function: &str, value: &LiteralValue, seconds: f64) -> Result<LiteralValue, Error> {
+    Ok(LiteralValue::Number(expect_number(interpreter, function, value)? * seconds))
+}
+
+fn milliseconds(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    scaled(interpreter, "milliseconds", &args[0], 0.001)
+}
+
+fn seconds(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    scaled(interpreter, "seconds", &args[0], 1.0)
+}
+
+fn minutes(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    scaled(interpreter, "minutes", &args[0], 60.0)
+}
+
+fn hours(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    scaled(interpreter, "hours", &args[0], 3600.0)
+}
+
+fn days(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    scaled(interpreter, "days", &args[0], 86400.0)
+}
+
+fn weeks(interpreter: &mut Interpreter, args: Vec<LiteralValue>) -> Result<LiteralValue, Error> {
+    scaled(interpreter, "weeks", &args[0], 604800.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lox::clock::ManualClock;
+    use crate::lox::error_manager::ErrorManager;
+    use std::cell::RefCell;
+
+    // 2024-02-29T12:00:00Z
+    const START: f64 = 1709208000.0;
+
+    fn interpreter_with_manual_clock() -> (Interpreter, Rc<ManualClock>) {
+        let error_manager = Rc::new(RefCell::new(ErrorManager::new()));
+        error_manager.borrow_mut().silent = true;
+        let mut interpreter = Interpreter::new(error_manager);
+        let clock = Rc::new(ManualClock::new(START));
+        interpreter.clock = clock.clone();
+        (interpreter, clock)
+    }
+
+    fn number(value: Result<LiteralValue, Error>) -> f64 {
+        match value {
+            Ok(LiteralValue::Number(n)) => n,
+            other => panic!("expected a number, got {:?}", other),
+        }
+    }
+
+    fn string(value: Result<LiteralValue, Error>) -> String {
+        match value {
+            Ok(LiteralValue::String(s)) => s,
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn manual_clock_is_frozen_until_advanced() {
+        let (mut interpreter, clock) = interpreter_with_manual_clock();
+        assert_eq!(number(now(&mut interpreter, vec![])), START);
+        assert_eq!(number(now(&mut interpreter, vec![])), START);
+        assert_eq!(number(super::clock(&mut interpreter, vec![])), 0.0);
+
+        clock.advance(90.0);
+        assert_eq!(number(now(&mut interpreter, vec![])), START + 90.0);
+        assert_eq!(number(super::clock(&mut interpreter, vec![])), 90.0);
+        let formatted = date_format(&mut interpreter, vec![LiteralValue::Number(START + 90.0)]);
+        assert_eq!(string(formatted), "2024-02-29T12:01:30Z");
+    }
+
+    #[test]
+    fn set_now_moves_only_the_wall_clock() {
+        let (mut interpreter, clock) = interpreter_with_manual_clock();
+        clock.set_now(0.0);
+        assert_eq!(number(now(&mut interpreter, vec![])), 0.0);
+        assert_eq!(number(super::clock(&mut interpreter, vec![])), 0.0);
+    }
+
+    #[test]
+    fn sleep_advances_a_manual_clock_instantly() {
+        let (mut interpreter, _clock) = interpreter_with_manual_clock();
+        assert!(sleep(&mut interpreter, vec![LiteralValue::Number(1500.0)]).is_ok());
+        assert_eq!(number(super::clock(&mut interpreter, vec![])), 1.5);
+        assert_eq!(number(now(&mut interpreter, vec![])), START + 1.5);
+    }
+
+    #[test]
+    fn sleep_rejects_durations_it_cannot_represent() {
+        let (mut interpreter, _clock) = interpreter_with_manual_clock();
+        for milliseconds in [1e300, -1.0, f64::NAN, f64::INFINITY] {
+            let result = sleep(&mut interpreter, vec![LiteralValue::Number(milliseconds)]);
+            assert!(matches!(result, Err(Error::Runtime(_))), "sleep({}) gave {:?}", milliseconds, result);
+        }
+    }
+
+    #[test]
+    fn date_rejects_years_out_of_range() {
+        let (mut interpreter, _clock) = interpreter_with_manual_clock();
+        let args = |year: f64| vec![LiteralValue::Number(year), LiteralValue::Number(1.0), LiteralValue::Number(1.0)];
+        assert!(matches!(date(&mut interpreter, args(1e18)), Err(Error::Runtime(_))));
+        assert!(matches!(date(&mut interpreter, args(-1e18)), Err(Error::Runtime(_))));
+        assert_eq!(number(date(&mut interpreter, args(1970.0))), 0.0);
+    }
+
+    #[test]
+    fn date_format_rejects_timestamps_out_of_range() {
+        let (mut interpreter, _clock) = interpreter_with_manual_clock();
+        for timestamp in [1e300, -1e300, f64::NAN] {
+            let result = date_format(&mut interpreter, vec![LiteralValue::Number(timestamp)]);
+            assert!(matches!(result, Err(Error::Runtime(_))), "date_format({}) gave {:?}", timestamp, result);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Source of time for the interpreter. Scripts only see time through this
+// trait, so embedders and tests can swap the wall clock for a `ManualClock`.
+// All values are in seconds.
+pub trait Clock {
+    // Seconds since an arbitrary starting point, never going backwards
+    fn monotonic(&self) -> f64;
+    // Seconds since the Unix epoch, UTC
+    fn now(&self) -> f64;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn monotonic(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+
+    fn now(&self) -> f64 {
+        // A system clock set before 1970 reads as negative time
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs_f64(),
+            Err(e) => -e.duration().as_secs_f64(),
+        }
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+// Frozen time that only moves when told to. Sleeping advances it instantly.
+// Only the tests install one so far.
+#[cfg_attr(not(test), allow(dead_code))]
+pub struct ManualClock {
+    monotonic: Cell<f64>,
+    now: Cell<f64>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl ManualClock {
+    pub fn new(now: f64) -> Self {
+        ManualClock { monotonic: Cell::new(0.0), now: Cell::new(now) }
+    }
+
+    pub fn advance(&self, seconds: f64) {
+        self.monotonic.set(self.monotonic.get() + seconds);
+        self.now.set(self.now.get() + seconds);
+    }
+
+    // Moves the wall clock only, like a system clock adjustment
+    pub fn set_now(&self, now: f64) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for ManualClock {
+    fn monotonic(&self) -> f64 {
+        self.monotonic.get()
+    }
+
+    fn now(&self) -> f64 {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration.as_secs_f64());
+    }
+}
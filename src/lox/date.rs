@@ -0,0 +1,407 @@
+// UTC calendar conversions and strftime-style formatting for the time built-ins.
+// Timestamps are seconds since the Unix epoch; the proleptic Gregorian
+// calendar is used for every year.
+
+const MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December"];
+const WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u32, // 1..=12
+    pub day: u32,   // 1..=31
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+// Years a date can have. Every timestamp within them is a whole number of
+// seconds that an f64 holds exactly.
+pub const MIN_YEAR: i64 = -1_000_000;
+pub const MAX_YEAR: i64 = 1_000_000;
+
+// Days since 1970-01-01, None when the year is out of range
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return None;
+    }
+    let year = if month <= 2 { year.checked_sub(1)? } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = (month as i64 + 9) % 12; // March is 0
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(146097)?.checked_add(day_of_era)?.checked_sub(719468)
+}
+
+// Timestamps from the start of MIN_YEAR up to, not including, the year after MAX_YEAR
+pub fn timestamp_in_range(timestamp: f64) -> bool {
+    let first = civil_days(MIN_YEAR, 1, 1) * 86400;
+    let end = (civil_days(MAX_YEAR, 12, 31) + 1) * 86400;
+    timestamp >= first as f64 && timestamp < end as f64
+}
+
+// Howard Hinnant's `days_from_civil`, for years already known to be in range
+fn civil_days(year: i64, month: u32, day: u32) -> i64 {
+    days_from_civil(year, month, day).unwrap_or_else(|| unreachable!("year {} is out of range", year))
+}
+
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// The methods below rely on the year being in range, which `new` and
+// `from_timestamp` check.
+impl DateTime {
+    pub fn new(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Result<DateTime, String> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return Err(format!("Year {} is out of range {}..{}", year, MIN_YEAR, MAX_YEAR));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(format!("Month {} is out of range", month));
+        }
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(format!("Day {} is out of range", day));
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(format!("Time {:02}:{:02}:{:02} is out of range", hour, minute, second));
+        }
+        Ok(DateTime { year, month, day, hour, minute, second })
+    }
+
+    // Fractions of a second are dropped
+    pub fn from_timestamp(timestamp: f64) -> Result<DateTime, String> {
+        if !timestamp_in_range(timestamp) {
+            return Err(format!("Timestamp {} is outside of the years {}..{}", timestamp, MIN_YEAR, MAX_YEAR));
+        }
+        let seconds = timestamp.floor() as i64;
+        let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+        let time = seconds.rem_euclid(86400) as u32;
+        Ok(DateTime { year, month, day, hour: time / 3600, minute: time % 3600 / 60, second: time % 60 })
+    }
+
+    pub fn timestamp(&self) -> f64 {
+        let days = civil_days(self.year, self.month, self.day);
+        (days * 86400 + (self.hour * 3600 + self.minute * 60 + self.second) as i64) as f64
+    }
+
+    // 0 is Sunday
+    pub fn weekday(&self) -> usize {
+        (civil_days(self.year, self.month, self.day) + 4).rem_euclid(7) as usize
+    }
+
+    pub fn day_of_year(&self) -> u32 {
+        (civil_days(self.year, self.month, self.day) - civil_days(self.year, 1, 1)) as u32 + 1
+    }
+
+    fn hour12(&self) -> u32 {
+        match self.hour % 12 {
+            0 => 12,
+            hour => hour,
+        }
+    }
+}
+
+// Supported directives: %Y %y %m %d %H %I %p %M %S %j %a %A %b %B %s %z %Z %F %T %%
+pub fn format(timestamp: f64, pattern: &str) -> Result<String, String> {
+    let date = DateTime::from_timestamp(timestamp)?;
+    let mut output = String::new();
+    let mut chars = pattern.chars().enumerate();
+    while let Some((_, c)) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+        let Some((column, directive)) = chars.next() else {
+            return Err(format!("Pattern ends with a lone '%' at column {}", pattern.chars().count()));
+        };
+        match directive {
+            'Y' => output.push_str(&format!("{:04}", date.year)),
+            'y' => output.push_str(&format!("{:02}", date.year.rem_euclid(100))),
+            'm' => output.push_str(&format!("{:02}", date.month)),
+            'd' => output.push_str(&format!("{:02}", date.day)),
+            'H' => output.push_str(&format!("{:02}", date.hour)),
+            'I' => output.push_str(&format!("{:02}", date.hour12())),
+            'p' => output.push_str(if date.hour < 12 { "AM" } else { "PM" }),
+            'M' => output.push_str(&format!("{:02}", date.minute)),
+            'S' => output.push_str(&format!("{:02}", date.second)),
+            'j' => output.push_str(&format!("{:03}", date.day_of_year())),
+            'a' => output.push_str(&WEEKDAYS[date.weekday()][..3]),
+            'A' => output.push_str(WEEKDAYS[date.weekday()]),
+            'b' => output.push_str(&MONTHS[date.month as usize - 1][..3]),
+            'B' => output.push_str(MONTHS[date.month as usize - 1]),
+            's' => output.push_str(&format!("{}", timestamp.floor() as i64)),
+            'z' => output.push_str("+0000"),
+            'Z' => output.push_str("UTC"),
+            'F' => output.push_str(&format!("{:04}-{:02}-{:02}", date.year, date.month, date.day)),
+            'T' => output.push_str(&format!("{:02}:{:02}:{:02}", date.hour, date.minute, date.second)),
+            '%' => output.push('%'),
+            other => return Err(format!("Unknown directive '%{}' at column {}", other, column)),
+        }
+    }
+    Ok(output)
+}
+
+// Reads `text` laid out as `pattern` and returns its timestamp. Accepts the
+// same directives as `format`; a space in the pattern matches any run of
+// whitespace. Fields that are not in the pattern default to 1970-01-01 00:00:00.
+pub fn parse(text: &str, pattern: &str) -> Result<f64, String> {
+    let mut parser = DateParser { text: text.chars().collect(), current: 0 };
+    let mut date = DateTime { year: 1970, month: 1, day: 1, hour: 0, minute: 0, second: 0 };
+    let mut offset = 0i64;
+    let mut pm = None;
+    let mut twelve_hour = false;
+    let mut epoch = None;
+    let mut day_of_year = None;
+
+    let mut chars = pattern.chars().enumerate();
+    while let Some((_, c)) = chars.next() {
+        if c.is_whitespace() {
+            parser.skip_whitespace();
+            continue;
+        }
+        if c != '%' {
+            parser.literal(c)?;
+            continue;
+        }
+        let Some((column, directive)) = chars.next() else {
+            return Err(format!("Pattern ends with a lone '%' at column {}", pattern.chars().count()));
+        };
+        match directive {
+            'Y' => date.year = parser.signed_number(9, "%Y")?,
+            'y' => {
+                // POSIX: 69-99 are 1969-1999, 00-68 are 2000-2068
+                let year = parser.number(2, "%y")? as i64;
+                date.year = if year >= 69 { 1900 + year } else { 2000 + year };
+            },
+            'm' => date.month = parser.number(2, "%m")?,
+            'd' => date.day = parser.number(2, "%d")?,
+            'H' => date.hour = parser.number(2, "%H")?,
+            'I' => {
+                date.hour = parser.number(2, "%I")?;
+                twelve_hour = true;
+            },
+            'p' => pm = Some(parser.word(&["AM", "PM"], "%p")? == 1),
+            'M' => date.minute = parser.number(2, "%M")?,
+            'S' => date.second = parser.number(2, "%S")?,
+            'j' => day_of_year = Some(parser.number(3, "%j")?),
+            'a' | 'A' => { parser.name(&WEEKDAYS, directive)?; },
+            'b' | 'B' => date.month = parser.name(&MONTHS, directive)? as u32 + 1,
+            's' => epoch = Some(parser.signed_number(20, "%s")?),
+            'z' => offset = parser.offset()?,
+            'Z' => { parser.word(&["UTC", "GMT"], "%Z")?; },
+            'F' => {
+                date.year = parser.signed_number(9, "%Y")?;
+                parser.literal('-')?;
+                date.month = parser.number(2, "%m")?;
+                parser.literal('-')?;
+                date.day = parser.number(2, "%d")?;
+            },
+            'T' => {
+                date.hour = parser.number(2, "%H")?;
+                parser.literal(':')?;
+                date.minute = parser.number(2, "%M")?;
+                parser.literal(':')?;
+                date.second = parser.number(2, "%S")?;
+            },
+            '%' => parser.literal('%')?,
+            other => return Err(format!("Unknown directive '%{}' at column {}", other, column)),
+        }
+    }
+    if parser.current < parser.text.len() {
+        return Err(format!("Unexpected text '{}' at position {}", parser.rest(), parser.current));
+    }
+    if let Some(seconds) = epoch {
+        let seconds = seconds as f64;
+        return DateTime::from_timestamp(seconds).map(|_| seconds);
+    }
+
+    if twelve_hour {
+        if !(1..=12).contains(&date.hour) {
+            return Err(format!("Hour {} is out of range for %I", date.hour));
+        }
+        date.hour %= 12;
+        if pm == Some(true) {
+            date.hour += 12;
+        }
+    }
+    if let Some(day_of_year) = day_of_year {
+        let days_in_year = if is_leap_year(date.year) { 366 } else { 365 };
+        if day_of_year < 1 || day_of_year > days_in_year {
+            return Err(format!("Day of year {} is out of range", day_of_year));
+        }
+        let Some(first_day) = days_from_civil(date.year, 1, 1) else {
+            return Err(format!("Year {} is out of range {}..{}", date.year, MIN_YEAR, MAX_YEAR));
+        };
+        let (_, month, day) = civil_from_days(first_day + day_of_year as i64 - 1);
+        date.month = month;
+        date.day = day;
+    }
+    let date = DateTime::new(date.year, date.month, date.day, date.hour, date.minute, date.second)?;
+    let timestamp = date.timestamp() - offset as f64;
+    // An offset can push the first or last day of the range over its edge
+    DateTime::from_timestamp(timestamp).map(|_| timestamp)
+}
+
+struct DateParser {
+    text: Vec<char>,
+    current: usize,
+}
+
+impl DateParser {
+    fn rest(&self) -> String {
+        self.text[self.current..].iter().collect()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.text.get(self.current).is_some_and(|c| c.is_whitespace()) {
+            self.current += 1;
+        }
+    }
+
+    fn literal(&mut self, expected: char) -> Result<(), String> {
+        if self.text.get(self.current) == Some(&expected) {
+            self.current += 1;
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' at position {}", expected, self.current))
+        }
+    }
+
+    // Between 1 and `max_digits` digits
+    fn number(&mut self, max_digits: usize, directive: &str) -> Result<u32, String> {
+        let start = self.current;
+        while self.current - start < max_digits && self.text.get(self.current).is_some_and(|c| c.is_ascii_digit()) {
+            self.current += 1;
+        }
+        if self.current == start {
+            return Err(format!("Expected a number for {} at position {}", directive, start));
+        }
+        Ok(self.text[start..self.current].iter().collect::<String>().parse().unwrap_or(0))
+    }
+
+    fn signed_number(&mut self, max_digits: usize, directive: &str) -> Result<i64, String> {
+        let negative = match self.text.get(self.current) {
+            Some('-') => { self.current += 1; true },
+            Some('+') => { self.current += 1; false },
+            _ => false,
+        };
+        let start = self.current;
+        while self.current - start < max_digits && self.text.get(self.current).is_some_and(|c| c.is_ascii_digit()) {
+            self.current += 1;
+        }
+        if self.current == start {
+            return Err(format!("Expected a number for {} at position {}", directive, start));
+        }
+        let value: i64 = self.text[start..self.current].iter().collect::<String>().parse()
+            .map_err(|_| format!("Number for {} at position {} is too large", directive, start))?;
+        Ok(if negative { -value } else { value })
+    }
+
+    // Index of the matching word, compared without case
+    fn word(&mut self, words: &[&str], directive: &str) -> Result<usize, String> {
+        for (index, word) in words.iter().enumerate() {
+            let length = word.chars().count();
+            let Some(candidate) = self.text.get(self.current..self.current + length) else {
+                continue;
+            };
+            if candidate.iter().collect::<String>().eq_ignore_ascii_case(word) {
+                self.current += length;
+                return Ok(index);
+            }
+        }
+        Err(format!("Expected {} at position {}", directive, self.current))
+    }
+
+    // A full name or its three-letter abbreviation
+    fn name(&mut self, names: &[&str], directive: char) -> Result<usize, String> {
+        let abbreviations: Vec<&str> = names.iter().map(|name| &name[..3]).collect();
+        let directive = format!("%{}", directive);
+        self.word(names, &directive).or_else(|_| self.word(&abbreviations, &directive))
+    }
+
+    // Z, +hhmm, -hhmm or +hh:mm, in seconds east of UTC
+    fn offset(&mut self) -> Result<i64, String> {
+        let sign = match self.text.get(self.current) {
+            Some('Z') => {
+                self.current += 1;
+                return Ok(0);
+            },
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return Err(format!("Expected a UTC offset for %z at position {}", self.current)),
+        };
+        self.current += 1;
+        let hours = self.number(2, "%z")? as i64;
+        if self.text.get(self.current) == Some(&':') {
+            self.current += 1;
+        }
+        let minutes = self.number(2, "%z")? as i64;
+        Ok(sign * (hours * 3600 + minutes * 60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_from_civil_counts_from_the_epoch() {
+        assert_eq!(days_from_civil(1970, 1, 1), Some(0));
+        assert_eq!(days_from_civil(2000, 3, 1), Some(11017));
+        assert_eq!(days_from_civil(1969, 12, 31), Some(-1));
+        assert_eq!(civil_from_days(11017), (2000, 3, 1));
+    }
+
+    #[test]
+    fn days_from_civil_rejects_years_out_of_range() {
+        assert_eq!(days_from_civil(MAX_YEAR + 1, 1, 1), None);
+        assert_eq!(days_from_civil(MIN_YEAR - 1, 1, 1), None);
+        assert_eq!(days_from_civil(i64::MAX, 1, 1), None);
+        assert_eq!(days_from_civil(i64::MIN, 1, 1), None);
+    }
+
+    #[test]
+    fn the_edges_of_the_range_round_trip() {
+        for (year, month, day) in [(MIN_YEAR, 1, 1), (MAX_YEAR, 12, 31)] {
+            let date = DateTime::new(year, month, day, 23, 59, 59).unwrap();
+            let back = DateTime::from_timestamp(date.timestamp()).unwrap();
+            assert_eq!(back, date);
+        }
+        let end = DateTime::new(MAX_YEAR, 12, 31, 23, 59, 59).unwrap().timestamp() + 1.0;
+        assert!(DateTime::from_timestamp(end).is_err());
+        assert!(DateTime::new(MAX_YEAR + 1, 1, 1, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn format_and_parse_reject_dates_out_of_range() {
+        assert!(format(1e300, "%Y").is_err());
+        assert!(format(f64::NAN, "%Y").is_err());
+        assert!(parse("999999999-01-01", "%F").is_err());
+        assert!(parse("99999999999999999999", "%s").is_err());
+        assert_eq!(parse("1000000-12-31", "%F").map(|t| format(t, "%F")), Ok(Ok("1000000-12-31".to_string())));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::tool::generate_ast::LiteralValue;
+use std::cell::Cell;
+use std::fmt;
+
+// How values look to users, shared by `print`, `str()` and error messages.
+// At the top level a string prints as its bare text; inside a collection,
+// and in `repr()`, strings are quoted so `("a, b",)` can't be misread.
+// Numbers drop a trailing `.0` (`3`, `0.5`) and switch to exponent notation
+// when very large or small (`1e+21`, `1e-7`), which Lox source can't write.
+// The non-finite values are `inf`, `-inf` and `nan`.
+
+// Collections nested deeper than this print as `...`
+const MAX_DEPTH: usize = 32;
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+pub fn format_number(n: f64) -> String {
+    // Display would give `NaN`, next to its own `inf`
+    if n.is_nan() {
+        return "nan".to_string();
+    }
+    let magnitude = n.abs();
+    if n.is_finite() && n != 0.0 && !(1e-7..1e21).contains(&magnitude) {
+        let text = format!("{:e}", n);
+        // 1e21 -> 1e+21, matching how most languages print it
+        return match text.split_once('e') {
+            Some((mantissa, exponent)) if !exponent.starts_with('-') => format!("{}e+{}", mantissa, exponent),
+            _ => text,
+        };
+    }
+    // Display for f64 already drops the `.0` of integral values
+    format!("{}", n)
+}
+
+impl fmt::Display for LiteralValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LiteralValue::String(s) => write!(f, "{}", s),
+            other => fmt_nested(f, other),
+        }
+    }
+}
+
+// The quoted form of a value, for error messages and nested output
+pub struct Repr<'a>(&'a LiteralValue);
+
+impl fmt::Display for Repr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_nested(f, self.0)
+    }
+}
+
+impl LiteralValue {
+    pub fn repr(&self) -> Repr<'_> {
+        Repr(self)
+    }
+}
+
+pub fn fmt_nested(f: &mut fmt::Formatter, value: &LiteralValue) -> fmt::Result {
+    match value {
+        LiteralValue::Number(n) => write!(f, "{}", format_number(*n)),
+        LiteralValue::String(s) => write!(f, "{:?}", s),
+        LiteralValue::Boolean(b) => write!(f, "{}", b),
+        LiteralValue::Nil => write!(f, "nil"),
+        LiteralValue::RecordType(record_type) => write!(f, "<record {}>", record_type.name),
+        LiteralValue::Enum(enum_type) => write!(f, "<enum {}>", enum_type.name),
+        LiteralValue::VariantConstructor(variant) => write!(f, "<variant {}.{}>", variant.enum_name, variant.name),
+        LiteralValue::Function(function) => write!(f, "{:?}", function),
+        LiteralValue::Native(function) => write!(f, "{:?}", function),
+        LiteralValue::BoundMethod(method) => write!(f, "{:?}", method.method),
+        LiteralValue::Namespace(namespace) => write!(f, "{:?}", namespace),
+        collection => {
+            let depth = DEPTH.with(|depth| depth.get());
+            if depth >= MAX_DEPTH {
+                return write!(f, "...");
+            }
+            DEPTH.with(|cell| cell.set(depth + 1));
+            let result = match collection {
+                LiteralValue::Tuple(elements) => fmt_tuple(f, elements),
+                LiteralValue::Record(record) => write!(f, "{}", record),
+                LiteralValue::Variant(variant) => write!(f, "{}", variant),
+                LiteralValue::Set(set) => write!(f, "{}", set),
+                LiteralValue::Map(map) => write!(f, "{}", map),
+                _ => Ok(()),
+            };
+            DEPTH.with(|cell| cell.set(depth));
+            result
+        },
+    }
+}
+
+// `()`, `(1,)`, `(1, 2)`, the same way tuples are written in source
+fn fmt_tuple(f: &mut fmt::Formatter, elements: &[LiteralValue]) -> fmt::Result {
+    write!(f, "(")?;
+    for (index, element) in elements.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        fmt_nested(f, element)?;
+    }
+    if elements.len() == 1 {
+        write!(f, ",")?;
+    }
+    write!(f, ")")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_drop_a_trailing_zero_fraction() {
+        assert_eq!(format_number(3.0), "3");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_number(-2.25), "-2.25");
+    }
+
+    #[test]
+    fn large_and_small_numbers_use_exponents() {
+        assert_eq!(format_number(1e21), "1e+21");
+        assert_eq!(format_number(1.5e-8), "1.5e-8");
+    }
+
+    #[test]
+    fn non_finite_numbers_are_lowercase() {
+        assert_eq!(format_number(f64::INFINITY), "inf");
+        assert_eq!(format_number(f64::NEG_INFINITY), "-inf");
+        assert_eq!(format_number(f64::NAN), "nan");
+        assert_eq!(format_number(-f64::NAN), "nan");
+    }
+}
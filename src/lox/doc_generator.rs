@@ -0,0 +1,99 @@
+use crate::tool::generate_ast::Stmt;
+
+// Renders the top-level declarations of a script, with their `///` docs,
+// as a reference page for `lox doc`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DocFormat::Markdown => "md",
+            DocFormat::Html => "html",
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    signature: String,
+    doc: Option<String>,
+}
+
+fn typed(name: &str, annotation: &Option<String>) -> String {
+    match annotation {
+        Some(annotation) => format!("{}: {}", name, annotation),
+        None => name.to_string(),
+    }
+}
+
+fn entry(statement: &Stmt) -> Option<Entry> {
+    let (name, signature, doc) = match statement {
+        Stmt::Var { name, type_annotation, doc, .. } => (name, format!("var {}", typed(name, type_annotation)), doc),
+        Stmt::Const { name, type_annotation, doc, .. } => (name, format!("const {}", typed(name, type_annotation)), doc),
+        Stmt::Function { name, parameters, return_type, doc, .. } => {
+            let parameters: Vec<String> = parameters.iter().map(|(name, annotation)| typed(name, annotation)).collect();
+            let signature = format!("fun {}", typed(&format!("{}({})", name, parameters.join(", ")), return_type));
+            (name, signature, doc)
+        },
+        Stmt::Record { name, fields, doc, .. } => (name, format!("record {}({})", name, fields.join(", ")), doc),
+        Stmt::Enum { name, variants, doc, .. } => {
+            let variants: Vec<String> = variants.iter().map(|(variant, fields)| {
+                if fields.is_empty() { variant.clone() } else { format!("{}({})", variant, fields.join(", ")) }
+            }).collect();
+            (name, format!("enum {} {{ {} }}", name, variants.join(", ")), doc)
+        },
+        _ => return None,
+    };
+    Some(Entry { name: name.clone(), signature, doc: doc.clone() })
+}
+
+pub fn render(title: &str, statements: &[Stmt], format: DocFormat) -> String {
+    let entries: Vec<Entry> = statements.iter().filter_map(entry).collect();
+    match format {
+        DocFormat::Markdown => render_markdown(title, &entries),
+        DocFormat::Html => render_html(title, &entries),
+    }
+}
+
+fn render_markdown(title: &str, entries: &[Entry]) -> String {
+    let mut page = format!("# {}\n", title);
+    if entries.is_empty() {
+        page.push_str("\nNo declarations.\n");
+    }
+    for entry in entries {
+        page.push_str(&format!("\n## {}\n\n```lox\n{}\n```\n", entry.name, entry.signature));
+        if let Some(doc) = &entry.doc {
+            page.push_str(&format!("\n{}\n", doc));
+        }
+    }
+    page
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Doc text is shown as plain paragraphs, split on blank lines
+fn render_html(title: &str, entries: &[Entry]) -> String {
+    let title = escape_html(title);
+    let mut page = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n", title, title);
+    if entries.is_empty() {
+        page.push_str("<p>No declarations.</p>\n");
+    }
+    for entry in entries {
+        let name = escape_html(&entry.name);
+        page.push_str(&format!("<section id=\"{}\">\n<h2>{}</h2>\n<pre><code>{}</code></pre>\n", name, name, escape_html(&entry.signature)));
+        if let Some(doc) = &entry.doc {
+            for paragraph in doc.split("\n\n").filter(|paragraph| !paragraph.trim().is_empty()) {
+                page.push_str(&format!("<p>{}</p>\n", escape_html(paragraph.trim())));
+            }
+        }
+        page.push_str("</section>\n");
+    }
+    page.push_str("</body>\n</html>\n");
+    page
+}
@@ -0,0 +1,98 @@
+use crate::lox::display::fmt_nested;
+use crate::tool::generate_ast::LiteralValue;
+use std::fmt;
+use std::rc::Rc;
+
+// One case of an enum, e.g. `Rect(w, h)` in `enum Shape { Rect(w, h) }`
+#[derive(Debug)]
+pub struct VariantType {
+    pub enum_name: String,
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+// Variants of separately declared enums differ even when spelled the same
+impl PartialEq for VariantType {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl VariantType {
+    pub fn field_index(&self, field: &str) -> Option<usize> {
+        self.fields.iter().position(|name| name == field)
+    }
+}
+
+// An `enum Shape { ... }` declaration, bound to the enum's name.
+// Its variants are reached as properties: `Shape.Circle(1)`, `Shape.Empty`.
+#[derive(Debug)]
+pub struct EnumType {
+    pub name: String,
+    pub variants: Vec<Rc<VariantType>>,
+}
+
+impl PartialEq for EnumType {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl EnumType {
+    pub fn new(name: String, variants: Vec<(String, Vec<String>)>) -> Self {
+        let variants = variants.into_iter().map(|(variant, fields)| Rc::new(VariantType {
+            enum_name: name.clone(),
+            name: variant,
+            fields,
+        })).collect();
+        EnumType { name, variants }
+    }
+
+    pub fn variant(&self, name: &str) -> Option<Rc<VariantType>> {
+        self.variants.iter().find(|variant| variant.name == name).cloned()
+    }
+
+    pub fn declares(&self, variant: &VariantType) -> bool {
+        self.variants.iter().any(|own| **own == *variant)
+    }
+}
+
+// A value of one of the variants together with its payload
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantValue {
+    pub variant: Rc<VariantType>,
+    pub values: Vec<LiteralValue>,
+}
+
+impl VariantValue {
+    pub fn new(variant: Rc<VariantType>, values: Vec<LiteralValue>) -> Self {
+        VariantValue { variant, values }
+    }
+
+    pub fn get(&self, field: &str) -> Option<LiteralValue> {
+        self.variant.field_index(field).map(|index| self.values[index].clone())
+    }
+
+    pub fn is_variant(&self, variant: &VariantType) -> bool {
+        *self.variant == *variant
+    }
+}
+
+// Prints as `Shape.Rect(w: 1, h: 2)`, or `Shape.Empty` without payload
+impl fmt::Display for VariantValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.variant.enum_name, self.variant.name)?;
+        if self.variant.fields.is_empty() {
+            return Ok(());
+        }
+        write!(f, "(")?;
+        for (index, (field, value)) in self.variant.fields.iter().zip(&self.values).enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: ", field)?;
+            fmt_nested(f, value)?;
+        }
+        write!(f, ")")
+    }
+}
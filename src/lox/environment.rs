@@ -1,13 +1,13 @@
-
-use crate::lox::error_manager::ErrorManager;
+use crate::lox::error_manager::{ErrorManager, Error};
 use crate::tool::generate_ast::LiteralValue;
+use std::collections::HashSet;
 use std::rc::Rc;
 use std::cell::RefCell;
-use crate::lox::token::Token;
 
 #[derive(Debug, Clone)]
 pub struct Environment {
     values: std::collections::HashMap<String, LiteralValue>,
+    constants: HashSet<String>,
     error_manager: Rc<RefCell<ErrorManager>>,
     pub enclosing: Option<Rc<RefCell<Environment>>>,
 }
@@ -16,6 +16,7 @@ impl Environment {
     pub fn new(error_manager: Rc<RefCell<ErrorManager>>) -> Self {
         Environment {
             values: std::collections::HashMap::new(),
+            constants: HashSet::new(),
             error_manager,
             enclosing: None,
         }
@@ -23,6 +24,12 @@ impl Environment {
     }
 
     pub fn define(&mut self, name: String, value: LiteralValue) {
+        self.constants.remove(&name);
+        self.values.insert(name, value);
+    }
+
+    pub fn define_constant(&mut self, name: String, value: LiteralValue) {
+        self.constants.insert(name.clone());
         self.values.insert(name, value);
     }
 
@@ -37,6 +44,34 @@ impl Environment {
         None
     }
 
+    // Updates an existing binding in the nearest scope that declares it.
+    // The resolver rejects assignments to constants before execution; this
+    // check only catches the cases it cannot see statically.
+    pub fn assign(&mut self, name: &str, value: LiteralValue) -> Result<(), Error> {
+        if self.values.contains_key(name) {
+            if self.constants.contains(name) {
+                return Err(self.error_manager.borrow_mut().report_runtime_error(
+                    &format!("Cannot assign to constant '{}'.", name)));
+            }
+            self.values.insert(name.to_string(), value);
+            return Ok(());
+        } else if let Some(enclosing) = &self.enclosing {
+            return enclosing.borrow_mut().assign(name, value);
+        }
+        Err(self.error_manager.borrow_mut().report_runtime_error(
+            &format!("Undefined variable '{}'.", name)))
+    }
+
+    // Like `get`, but a missing name is not an error
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+            || self.enclosing.as_ref().is_some_and(|enclosing| enclosing.borrow().is_defined(name))
+    }
 
-    
-}
\ No newline at end of file
+    // The bindings declared in this scope only, sorted by name
+    pub fn bindings(&self) -> Vec<(String, LiteralValue)> {
+        let mut bindings: Vec<_> = self.values.iter().map(|(name, value)| (name.clone(), value.clone())).collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
+}
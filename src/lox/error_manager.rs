@@ -1,26 +1,58 @@
+use crate::tool::generate_ast::LiteralValue;
+
 #[derive(Debug)]
 pub enum Error {
     Runtime(String),
     Other(String),
+    // Control flow signals unwinding to the enclosing loop
+    Break(Option<String>),
+    Continue(Option<String>),
+    // Unwinds to the enclosing function call
+    Return(LiteralValue),
+    // Unwinds out of the whole program, `exit(code)`
+    Exit(i32),
+}
+
+// A static error seen while silent
+#[derive(Debug, Clone)]
+pub struct RecordedError {
+    pub line: usize,
+    // 1-based, when the error is tied to a token
+    pub column: Option<usize>,
+    pub message: String,
 }
 
 #[derive(Debug)]
 pub struct ErrorManager {
     pub had_error: bool,
     pub had_runtime_error: bool,
+    // Errors are only recorded, the caller reports them its own way
+    pub silent: bool,
+    pub recorded: Vec<RecordedError>,
 }
 
 
 impl ErrorManager {
     pub fn new() -> Self {
         Self { had_error: false, 
-        had_runtime_error: false }
+        had_runtime_error: false,
+        silent: false,
+        recorded: Vec::new() }
     }
 
     pub fn report(&mut self, line: usize, message: &str, about: Option<&str>) -> Error {
-        eprintln!("[line {}] Error: {}", line, message);
-        if about.is_some() {
-            eprintln!("About: {}", about.unwrap());
+        self.report_at(line, None, message, about)
+    }
+
+    // Like `report`, for errors whose column is known
+    pub fn report_at(&mut self, line: usize, column: Option<usize>, message: &str, about: Option<&str>) -> Error {
+        if !self.silent {
+            eprintln!("[line {}] Error: {}", line, message);
+            if let Some(about) = about {
+                eprintln!("About: {}", about);
+            }
+        } else {
+            self.recorded.push(RecordedError { line, column, message: message.to_string() });
         }
         self.had_error = true;
         Error::Other(message.to_string())
@@ -28,7 +60,9 @@ impl ErrorManager {
 
 
     pub fn report_runtime_error(&mut self, message: &str) -> Error {
-        eprintln!("Runtime Error: {}", message);
+        if !self.silent {
+            eprintln!("Runtime Error: {}", message);
+        }
         self.had_runtime_error = true;
         Error::Runtime(message.to_string())
     }
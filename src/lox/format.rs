@@ -0,0 +1,385 @@
+use crate::lox::display::format_number;
+use crate::tool::generate_ast::LiteralValue;
+use std::fmt;
+
+// The engine behind `format("{:>8.2} | {name}", x, name: y)`.
+// A placeholder is `{[argument][:spec]}` where spec is
+// `[[fill]align][sign][#][0][width][.precision][type]`, much like Python's.
+// The whole format string is parsed before any argument is looked at.
+
+#[derive(Debug)]
+pub struct FormatError {
+    pub message: String,
+    pub column: usize,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+    // Padding goes between the sign or radix prefix and the digits
+    AfterSign,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sign {
+    Negative,
+    Always,
+    Space,
+}
+
+#[derive(Debug)]
+enum Argument {
+    Next(usize),
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug)]
+struct Spec {
+    fill: char,
+    align: Option<Align>,
+    sign: Option<Sign>,
+    alternate: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Text(String),
+    Placeholder { argument: Argument, spec: Spec, column: usize },
+}
+
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+pub fn parse(format: &str) -> Result<Template, FormatError> {
+    let chars: Vec<char> = format.chars().collect();
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut next_argument = 0;
+    // Like Python, `{}` and `{0}` can't be mixed in one format string
+    let mut numbering: Option<bool> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '{' if chars.get(i + 1) == Some(&'{') => {
+                text.push('{');
+                i += 2;
+            },
+            '}' if chars.get(i + 1) == Some(&'}') => {
+                text.push('}');
+                i += 2;
+            },
+            '}' => return Err(error("Single '}' must be written as '}}'", i)),
+            '{' => {
+                let start = i;
+                let Some(offset) = chars[i..].iter().position(|c| *c == '}') else {
+                    return Err(error("Unclosed '{'", start));
+                };
+                let end = i + offset;
+                let (name_end, spec) = match chars[start + 1..end].iter().position(|c| *c == ':') {
+                    Some(colon) => (start + 1 + colon, parse_spec(&chars, start + 2 + colon, end)?),
+                    None => (end, Spec::default()),
+                };
+                let name: String = chars[start + 1..name_end].iter().collect();
+                let index = name.parse::<usize>().ok();
+                if name.is_empty() || index.is_some() {
+                    let automatic = name.is_empty();
+                    if numbering.is_some_and(|previous| previous != automatic) {
+                        return Err(error("Cannot mix automatic '{}' and numbered '{0}' placeholders", start));
+                    }
+                    numbering = Some(automatic);
+                }
+                let argument = if name.is_empty() {
+                    next_argument += 1;
+                    Argument::Next(next_argument - 1)
+                } else if let Some(index) = index {
+                    Argument::Index(index)
+                } else if name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    Argument::Name(name)
+                } else {
+                    return Err(error(&format!("Invalid placeholder name '{}'", name), start + 1));
+                };
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Placeholder { argument, spec, column: start + 1 });
+                i = end + 1;
+            },
+            c => {
+                text.push(c);
+                i += 1;
+            },
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(Template { segments })
+}
+
+impl Default for Spec {
+    fn default() -> Self {
+        Spec { fill: ' ', align: None, sign: None, alternate: false, width: 0, precision: None, kind: None }
+    }
+}
+
+fn align_for(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '>' => Some(Align::Right),
+        '^' => Some(Align::Center),
+        '=' => Some(Align::AfterSign),
+        _ => None,
+    }
+}
+
+// Parses chars[start..end], the part after the ':'
+fn parse_spec(chars: &[char], start: usize, end: usize) -> Result<Spec, FormatError> {
+    let mut spec = Spec::default();
+    let mut i = start;
+    let at = |i: usize| if i < end { Some(chars[i]) } else { None };
+
+    if let Some(align) = at(i + 1).and_then(align_for) {
+        spec.fill = chars[i];
+        spec.align = Some(align);
+        i += 2;
+    } else if let Some(align) = at(i).and_then(align_for) {
+        spec.align = Some(align);
+        i += 1;
+    }
+    match at(i) {
+        Some('+') => spec.sign = Some(Sign::Always),
+        Some('-') => spec.sign = Some(Sign::Negative),
+        Some(' ') => spec.sign = Some(Sign::Space),
+        _ => {},
+    }
+    if spec.sign.is_some() {
+        i += 1;
+    }
+    if at(i) == Some('#') {
+        spec.alternate = true;
+        i += 1;
+    }
+    if at(i) == Some('0') {
+        if spec.align.is_none() {
+            spec.fill = '0';
+            spec.align = Some(Align::AfterSign);
+        }
+        i += 1;
+    }
+    let (width, next) = digits(chars, i, end, "Width")?;
+    spec.width = width.unwrap_or(0);
+    i = next;
+    if at(i) == Some('.') {
+        let (precision, next) = digits(chars, i + 1, end, "Precision")?;
+        if precision.is_none() {
+            return Err(error("Expected digits after '.'", i + 1));
+        }
+        spec.precision = precision;
+        i = next;
+    }
+    if let Some(kind) = at(i) {
+        if !"sdfexXbo%".contains(kind) {
+            return Err(error(&format!("Unknown format type '{}'", kind), i));
+        }
+        spec.kind = Some(kind);
+        i += 1;
+    }
+    if i < end {
+        return Err(error(&format!("Unexpected '{}' in format spec", chars[i]), i));
+    }
+    Ok(spec)
+}
+
+// Widths and precisions beyond this would only build huge strings
+const MAX_WIDTH: usize = 10_000;
+
+// The number in chars[start..], if any, and the index after it
+fn digits(chars: &[char], start: usize, end: usize, what: &str) -> Result<(Option<usize>, usize), FormatError> {
+    let mut i = start;
+    while i < end && chars[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i == start {
+        return Ok((None, i));
+    }
+    let text: String = chars[start..i].iter().collect();
+    match text.parse::<usize>() {
+        Ok(value) if value <= MAX_WIDTH => Ok((Some(value), i)),
+        _ => Err(error(&format!("{} {} is larger than {}", what, text, MAX_WIDTH), start)),
+    }
+}
+
+// `index` is 0-based, columns are reported 1-based
+fn error(message: &str, index: usize) -> FormatError {
+    FormatError { message: message.to_string(), column: index + 1 }
+}
+
+impl Template {
+    pub fn render(&self, positional: &[LiteralValue], named: &[(String, LiteralValue)]) -> Result<String, FormatError> {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Placeholder { argument, spec, column } => {
+                    let fail = |message: String| FormatError { message, column: *column };
+                    let value = match argument {
+                        Argument::Next(index) | Argument::Index(index) => positional.get(*index)
+                            .ok_or_else(|| fail(format!("No argument at index {} ({} given)", index, positional.len())))?,
+                        Argument::Name(name) => named.iter().find(|(n, _)| n == name).map(|(_, value)| value)
+                            .ok_or_else(|| fail(format!("No argument named '{}'", name)))?,
+                    };
+                    out.push_str(&format_value(value, spec).map_err(fail)?);
+                },
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn format_value(value: &LiteralValue, spec: &Spec) -> Result<String, String> {
+    let number = match value {
+        LiteralValue::Number(n) => Some(*n),
+        _ => None,
+    };
+    let Some(n) = number else {
+        if spec.kind.is_some_and(|kind| kind != 's') {
+            return Err(format!("Format type '{}' needs a number but got {}", spec.kind.unwrap(), value.repr()));
+        }
+        if spec.sign.is_some() || spec.align == Some(Align::AfterSign) {
+            return Err(format!("Sign options need a number but got {}", value.repr()));
+        }
+        let mut text = value.to_string();
+        if let Some(precision) = spec.precision {
+            text = text.chars().take(precision).collect();
+        }
+        return Ok(pad("", &text, spec, Align::Left));
+    };
+
+    let integer = || {
+        if n.fract() == 0.0 && n.is_finite() && n.abs() < 2f64.powi(63) {
+            Ok(n.abs() as u64)
+        } else {
+            Err(format!("Format type '{}' needs an integer but got {}", spec.kind.unwrap(), value.repr()))
+        }
+    };
+    let (digits, prefix) = match spec.kind {
+        // Spelled as `print` does, whatever the precision
+        None | Some('s' | 'f' | 'e') if !n.is_finite() => (format_number(n.abs()), ""),
+        Some('%') if !n.is_finite() => (format!("{}%", format_number(n.abs())), ""),
+        None | Some('s') => match spec.precision {
+            Some(precision) => (format!("{:.*}", precision, n.abs()), ""),
+            None => (format_number(n.abs()), ""),
+        },
+        Some('d') => (integer()?.to_string(), ""),
+        Some('f') => (format!("{:.*}", spec.precision.unwrap_or(6), n.abs()), ""),
+        Some('%') => (format!("{:.*}%", spec.precision.unwrap_or(6), n.abs() * 100.0), ""),
+        Some('e') => (exponent(n.abs(), spec.precision.unwrap_or(6)), ""),
+        Some('x') => (format!("{:x}", integer()?), "0x"),
+        Some('X') => (format!("{:X}", integer()?), "0X"),
+        Some('b') => (format!("{:b}", integer()?), "0b"),
+        Some('o') => (format!("{:o}", integer()?), "0o"),
+        Some(kind) => unreachable!("format type '{}' is rejected by parse", kind),
+    };
+    // NaN can carry a sign bit, but `-nan` means nothing
+    let sign = if n.is_sign_negative() && n != 0.0 && !n.is_nan() {
+        "-"
+    } else {
+        match spec.sign {
+            Some(Sign::Always) => "+",
+            Some(Sign::Space) => " ",
+            _ => "",
+        }
+    };
+    let prefix = if spec.alternate { prefix } else { "" };
+    Ok(pad(&format!("{}{}", sign, prefix), &digits, spec, Align::Right))
+}
+
+// 12345.0 with precision 2 -> "1.23e+04"
+fn exponent(n: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, n);
+    match text.split_once('e') {
+        Some((mantissa, exponent)) => {
+            let (sign, digits) = match exponent.strip_prefix('-') {
+                Some(digits) => ('-', digits),
+                None => ('+', exponent),
+            };
+            format!("{}e{}{:0>2}", mantissa, sign, digits)
+        },
+        None => text,
+    }
+}
+
+fn pad(prefix: &str, body: &str, spec: &Spec, default: Align) -> String {
+    let length = prefix.chars().count() + body.chars().count();
+    let padding = spec.width.saturating_sub(length);
+    let fill = |count: usize| spec.fill.to_string().repeat(count);
+    match spec.align.unwrap_or(default) {
+        Align::Left => format!("{}{}{}", prefix, body, fill(padding)),
+        Align::Right => format!("{}{}{}", fill(padding), prefix, body),
+        Align::Center => format!("{}{}{}{}", fill(padding / 2), prefix, body, fill(padding - padding / 2)),
+        Align::AfterSign => format!("{}{}{}", prefix, fill(padding), body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: &str, value: f64) -> Result<String, FormatError> {
+        parse(format)?.render(&[LiteralValue::Number(value)], &[])
+    }
+
+    #[test]
+    fn width_and_precision_are_applied() {
+        assert_eq!(render("{:>8.2}|", 12.345).unwrap(), "   12.35|");
+        assert_eq!(render("{:05}", 42.0).unwrap(), "00042");
+    }
+
+    #[test]
+    fn width_and_precision_up_to_the_limit_are_accepted() {
+        assert_eq!(render("{:10000}", 1.0).unwrap().len(), 10_000);
+        assert!(render("{:.10000}", 1.0).is_ok());
+    }
+
+    #[test]
+    fn width_above_the_limit_is_an_error_at_its_column() {
+        let error = render("{:9999999999}|", 1.0).unwrap_err();
+        assert_eq!(error.column, 3);
+        assert_eq!(error.message, "Width 9999999999 is larger than 10000");
+    }
+
+    #[test]
+    fn width_that_does_not_fit_a_number_is_an_error() {
+        let error = render("ab{:99999999999999999999}", 1.0).unwrap_err();
+        assert_eq!(error.column, 5);
+    }
+
+    #[test]
+    fn precision_above_the_limit_is_an_error_at_its_column() {
+        let error = render("{:.10001f}", 1.0).unwrap_err();
+        assert_eq!(error.column, 4);
+        assert_eq!(error.message, "Precision 10001 is larger than 10000");
+    }
+
+    #[test]
+    fn non_finite_numbers_are_spelled_like_print() {
+        assert_eq!(render("{:.2f}", f64::NAN).unwrap(), "nan");
+        assert_eq!(render("{}", -f64::NAN).unwrap(), "nan");
+        assert_eq!(render("{:+e}", f64::INFINITY).unwrap(), "+inf");
+        assert_eq!(render("{:%}", f64::NEG_INFINITY).unwrap(), "-inf%");
+    }
+}
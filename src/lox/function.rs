@@ -0,0 +1,37 @@
+use crate::lox::environment::Environment;
+use crate::tool::generate_ast::Stmt;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+// A user function declared with `fun`, closing over the scope it was declared in.
+pub struct LoxFunction {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub body: Vec<Stmt>,
+    pub closure: Rc<RefCell<Environment>>,
+}
+
+impl LoxFunction {
+    pub fn new(name: String, parameters: Vec<String>, body: Vec<Stmt>, closure: Rc<RefCell<Environment>>) -> Self {
+        LoxFunction { name, parameters, body, closure }
+    }
+
+    pub fn arity(&self) -> usize {
+        self.parameters.len()
+    }
+}
+
+// Functions are equal only to themselves
+impl PartialEq for LoxFunction {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+// The closure can contain the function itself, so it is never printed
+impl fmt::Debug for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<fn {}>", self.name)
+    }
+}
@@ -1,24 +1,84 @@
 use crate::tool::generate_ast::LiteralValue;
-use crate::{lox::error_manager::ErrorManager, tool::generate_ast::{Expr, Stmt}, lox::token::TokenType};
-use crate::lox::error_manager::{self, Error};
+use crate::{lox::error_manager::ErrorManager, tool::generate_ast::{Expr, Stmt}};
+use crate::lox::error_manager::Error;
 use crate::lox::environment::Environment;
+use crate::lox::record::{RecordInstance, RecordType};
+use crate::lox::enumeration::{EnumType, VariantValue};
+use crate::lox::function::LoxFunction;
+use crate::lox::native::{BoundMethod, NativeFunction};
+use crate::lox::builtins;
+use crate::lox::slice;
+use crate::lox::map::LoxMap;
+use crate::lox::permissions::Permissions;
+use crate::lox::clock::{Clock, SystemClock};
+use crate::lox::rng::Rng;
+use crate::lox::terminal::TerminalState;
 use std::rc::Rc;
 use std::cell::RefCell;
 
 
 pub struct Interpreter {
     error_manager: Rc<RefCell<ErrorManager>>,
-    environment: Environment,
+    environment: Rc<RefCell<Environment>>,
+    pub permissions: Permissions,
+    pub clock: Rc<dyn Clock>,
+    pub rng: Rng,
+    pub terminal: TerminalState,
+    // Statements deferred by each block being executed, innermost last
+    deferred: Vec<Vec<Stmt>>,
+    #[cfg(feature = "regex")]
+    pub regex_cache: builtins::regexes::RegexCache,
     }
 
 impl Interpreter {
     pub fn new(error_manager: Rc<RefCell<ErrorManager>>) -> Self {
+        let mut globals = Environment::new(error_manager.clone());
+        builtins::define_globals(&mut globals);
         Interpreter { 
-            error_manager: error_manager.clone(), 
-            environment: Environment::new(error_manager), // or pass the appropriate parent environment if needed
+            error_manager, 
+            environment: Rc::new(RefCell::new(globals)), // or pass the appropriate parent environment if needed
+            permissions: Permissions::new(),
+            clock: Rc::new(SystemClock::new()),
+            rng: Rng::from_entropy(),
+            terminal: TerminalState::default(),
+            deferred: Vec::new(),
+            #[cfg(feature = "regex")]
+            regex_cache: builtins::regexes::RegexCache::new(),
         }
     }
 
+    // Overrides a global defined by the built-ins, e.g. `args` from the command line
+    pub fn define_global(&mut self, name: &str, value: LiteralValue) {
+        self.environment.borrow_mut().define_constant(name.to_string(), value);
+    }
+
+    // The scope a native function was called from
+    pub fn environment(&self) -> Rc<RefCell<Environment>> {
+        self.environment.clone()
+    }
+
+    // Reports a runtime error raised by a native function
+    pub fn runtime_error(&self, message: &str) -> Error {
+        self.error_manager.borrow_mut().report_runtime_error(message)
+    }
+
+    // Runs `f` without printing its errors or counting them against the script,
+    // for natives that hand errors back to the script as values
+    pub fn with_errors_silenced<T>(&mut self, f: impl FnOnce(&mut Interpreter) -> T) -> T {
+        let (silent, had_error, had_runtime_error, recorded) = {
+            let manager = self.error_manager.borrow();
+            (manager.silent, manager.had_error, manager.had_runtime_error, manager.recorded.len())
+        };
+        self.error_manager.borrow_mut().silent = true;
+        let result = f(self);
+        let mut manager = self.error_manager.borrow_mut();
+        manager.silent = silent;
+        manager.had_error = had_error;
+        manager.had_runtime_error = had_runtime_error;
+        manager.recorded.truncate(recorded);
+        result
+    }
+
     fn check_number_operand(&mut self, operator: &str, operand: &LiteralValue) -> Result<(), Error> {
         if let LiteralValue::Number(_) = operand {
             Ok(())
@@ -34,11 +94,21 @@ impl Interpreter {
             Expr::Literal{value} => {
                 Ok(value.clone())
             },
-            Expr::Binary { left, operator, right } => {
+            Expr::Binary { left, operator, right, .. } => {
                 let left_value = self.evaluate(left)?;
-                self.check_number_operand(operator, &left_value)?;
                 let right_value = self.evaluate(right)?;
-                self.check_number_operand(operator, &right_value)?;
+                if operator == "in" {
+                    return self.contains(&right_value, &left_value);
+                }
+                // String concatenation
+                if let ("+", LiteralValue::String(l), LiteralValue::String(r)) = (operator.as_str(), &left_value, &right_value) {
+                    return Ok(LiteralValue::String(format!("{}{}", l, r)));
+                }
+                // Equality is structural and works on every value
+                if operator != "==" && operator != "!=" {
+                    self.check_number_operand(operator, &left_value)?;
+                    self.check_number_operand(operator, &right_value)?;
+                }
                 match operator.as_str() {
                     "+" => match (&left_value, &right_value) {
                         (LiteralValue::Number(l), LiteralValue::Number(r)) => Ok(LiteralValue::Number(l + r)),
@@ -56,8 +126,8 @@ impl Interpreter {
                         (LiteralValue::Number(l), LiteralValue::Number(r)) => Ok(LiteralValue::Number(l / r)),
                         _ => Err(Error::Runtime("Operands must be numbers".into())),
                     },
-                    "==" => Ok(LiteralValue::Boolean(&left_value == &right_value)),
-                    "!=" => Ok(LiteralValue::Boolean(&left_value != &right_value)),
+                    "==" => Ok(LiteralValue::Boolean(left_value == right_value)),
+                    "!=" => Ok(LiteralValue::Boolean(left_value != right_value)),
                     ">" => match (&left_value, &right_value) {
                         (LiteralValue::Number(l), LiteralValue::Number(r)) => Ok(LiteralValue::Boolean(l > r)),
                         _ => Err(Error::Runtime("Operands must be numbers".into())),
@@ -85,15 +155,15 @@ impl Interpreter {
                     _ => Err(Error::Runtime("Invalid operator for binary expression".into())),
                 }
             },
-            Expr::Unary { operator, right } => {
+            Expr::Unary { operator, right, .. } => {
                 let right_value = self.evaluate(right)?;
                 match operator.as_str() {
-                    "-" => match (&right_value) {
+                    "-" => match &right_value {
                         LiteralValue::Number(r) => Ok(LiteralValue::Number(-r)),
                         _ => Err(Error::Runtime("Right value must be a number".into())),
                     },
-                    "!" => match (&right_value) {
-                        (LiteralValue::Boolean(r)) => Ok(LiteralValue::Boolean(!r)),
+                    "!" => match &right_value {
+                        LiteralValue::Boolean(r) => Ok(LiteralValue::Boolean(!r)),
                         _ => Err(Error::Runtime("Right value must be a boolean".into())),
                     },
                     _ => Err(self.error_manager.borrow_mut().report_runtime_error(
@@ -104,18 +174,19 @@ impl Interpreter {
                 self.evaluate(expression)
             },
             Expr::Variable { name } => {
-                if let Some(value) = self.environment.get(name) {
+                let value = self.environment.borrow_mut().get(name);
+                if let Some(value) = value {
                     Ok(value.clone())
                 } else {
                     Err(self.error_manager.borrow_mut().report_runtime_error(
                         &format!("Undefined variable '{}'.", name)))
                 }
             },
-            Expr::Assignment { name, value }
+            Expr::Assignment { name, value, .. }
             => {
                 //println!("Assigning value to variable: {}", name);
                 let value = self.evaluate(value)?;
-                self.environment.define(name.clone(), value.clone());
+                self.environment.borrow_mut().assign(name, value.clone())?;
                 Ok(value)
             },
             Expr::Logical { left, operator, right } => {
@@ -152,17 +223,362 @@ impl Interpreter {
                         &format!("Unknown logical operator '{}'", operator)))
                 }
             },
-            _ => Err(self.error_manager.borrow_mut().report_runtime_error(
-                &format!("Unexpected expression type: {:?}", expression)))
+            Expr::Tuple { elements } => {
+                let mut values = Vec::with_capacity(elements.len());
+                for element in elements {
+                    values.push(self.evaluate(element)?);
+                }
+                Ok(LiteralValue::Tuple(values))
+            },
+            Expr::Map { entries, .. } => {
+                let map = LoxMap::new();
+                for (key, value) in entries {
+                    let key = self.evaluate(key)?;
+                    let value = self.evaluate(value)?;
+                    if let Err(key) = map.insert(key, value) {
+                        return Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Map keys must be nil, booleans, numbers, strings or tuples of those, got {}.", key.repr())));
+                    }
+                }
+                Ok(LiteralValue::Map(Rc::new(map)))
+            },
+            Expr::Call { callee, arguments, named_arguments, .. } => {
+                let callee = self.evaluate(callee)?;
+                let mut argument_values = Vec::with_capacity(arguments.len());
+                for argument in arguments {
+                    argument_values.push(self.evaluate(argument)?);
+                }
+                let mut named_values = Vec::with_capacity(named_arguments.len());
+                for (name, argument) in named_arguments {
+                    named_values.push((name.clone(), self.evaluate(argument)?));
+                }
+                self.call(callee, argument_values, named_values)
+            },
+            Expr::Get { object, name, .. } => {
+                let object = self.evaluate(object)?;
+                match &object {
+                    LiteralValue::Record(record) => match record.get(name) {
+                        Some(value) => Ok(value),
+                        None => Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Record '{}' has no field '{}'.", record.record_type.name, name))),
+                    },
+                    LiteralValue::Variant(variant) => match variant.get(name) {
+                        Some(value) => Ok(value),
+                        None => Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Variant '{}.{}' has no field '{}'.", variant.variant.enum_name, variant.variant.name, name))),
+                    },
+                    LiteralValue::Namespace(namespace) => match namespace.get(name) {
+                        Some(value) => Ok(value),
+                        None => Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Namespace '{}' has no member '{}'.", namespace.name, name))),
+                    },
+                    LiteralValue::Enum(enum_type) => match enum_type.variant(name) {
+                        // Variants without payload are values on their own
+                        Some(variant) if variant.fields.is_empty() =>
+                            Ok(LiteralValue::Variant(Rc::new(VariantValue::new(variant, Vec::new())))),
+                        Some(variant) => Ok(LiteralValue::VariantConstructor(variant)),
+                        None => Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Enum '{}' has no variant '{}'.", enum_type.name, name))),
+                    },
+                    _ => match builtins::method_for(&object, name) {
+                        Some(method) => Ok(LiteralValue::BoundMethod(Rc::new(BoundMethod::new(object.clone(), method)))),
+                        None => Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Undefined property '{}' on {}.", name, object.repr()))),
+                    },
+                }
+            },
+            Expr::Index { object, index, .. } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                if let LiteralValue::Map(map) = &object {
+                    return match map.get(&index) {
+                        Some(value) => Ok(value),
+                        None => Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Key {} not found in map.", index.repr()))),
+                    };
+                }
+                let index = self.expect_integer(&index, "Index")?;
+                let (len, element) = match &object {
+                    LiteralValue::String(s) => {
+                        let chars: Vec<char> = s.chars().collect();
+                        (chars.len(), slice::resolve_index(index, chars.len()).map(|i| LiteralValue::String(chars[i].to_string())))
+                    },
+                    LiteralValue::Tuple(elements) => {
+                        (elements.len(), slice::resolve_index(index, elements.len()).map(|i| elements[i].clone()))
+                    },
+                    _ => return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Only strings, tuples and maps can be indexed, got {}.", object.repr()))),
+                };
+                match element {
+                    Some(element) => Ok(element),
+                    None => Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Index {} out of range for length {}.", index, len))),
+                }
+            },
+            Expr::Slice { object, start, end, step, .. } => {
+                let object = self.evaluate(object)?;
+                let mut bounds = Vec::with_capacity(3);
+                for (part, what) in [(start, "Slice start"), (end, "Slice end"), (step, "Slice step")] {
+                    bounds.push(match part {
+                        Some(part) => {
+                            let value = self.evaluate(part)?;
+                            Some(self.expect_integer(&value, what)?)
+                        },
+                        None => None,
+                    });
+                }
+                let step = bounds[2].unwrap_or(1);
+                if step == 0 {
+                    return Err(self.error_manager.borrow_mut().report_runtime_error("Slice step cannot be zero."));
+                }
+                match &object {
+                    // Strings are sliced by Unicode scalar values, never by bytes
+                    LiteralValue::String(s) => {
+                        let chars: Vec<char> = s.chars().collect();
+                        let positions = slice::slice_positions(chars.len(), bounds[0], bounds[1], step);
+                        Ok(LiteralValue::String(positions.into_iter().map(|i| chars[i]).collect()))
+                    },
+                    LiteralValue::Tuple(elements) => {
+                        let positions = slice::slice_positions(elements.len(), bounds[0], bounds[1], step);
+                        Ok(LiteralValue::Tuple(positions.into_iter().map(|i| elements[i].clone()).collect()))
+                    },
+                    _ => Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Only strings and tuples can be sliced, got {}.", object.repr()))),
+                }
+            },
+            Expr::With { object, updates, .. } => {
+                let object = self.evaluate(object)?;
+                let LiteralValue::Record(record) = &object else {
+                    return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("'with' can only update records, got {}.", object.repr())));
+                };
+                let mut values = record.values.clone();
+                for (name, update) in updates {
+                    let Some(index) = record.record_type.field_index(name) else {
+                        return Err(self.error_manager.borrow_mut().report_runtime_error(
+                            &format!("Record '{}' has no field '{}'.", record.record_type.name, name)));
+                    };
+                    values[index] = self.evaluate(update)?;
+                }
+                Ok(LiteralValue::Record(Rc::new(RecordInstance::new(record.record_type.clone(), values))))
+            },
+            Expr::Is { value, kind, .. } => {
+                let value = self.evaluate(value)?;
+                let kind = self.evaluate(kind)?;
+                let matches = match (&value, &kind) {
+                    (LiteralValue::Variant(variant), LiteralValue::VariantConstructor(expected)) => variant.is_variant(expected),
+                    (LiteralValue::Variant(variant), LiteralValue::Variant(expected)) => variant.is_variant(&expected.variant),
+                    (LiteralValue::Variant(variant), LiteralValue::Enum(expected)) => expected.declares(&variant.variant),
+                    (LiteralValue::Record(record), LiteralValue::RecordType(expected)) => record.record_type == *expected,
+                    (_, LiteralValue::VariantConstructor(_) | LiteralValue::Variant(_) | LiteralValue::Enum(_) | LiteralValue::RecordType(_)) => false,
+                    _ => return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Right operand of 'is' must be a record, an enum or a variant, got {}.", kind.repr()))),
+                };
+                Ok(LiteralValue::Boolean(matches))
+            },
+            Expr::TupleAssignment { names, value, .. } => {
+                // The whole right-hand side is evaluated first, so `(a, b) = (b, a)` swaps
+                let value = self.evaluate(value)?;
+                let values = self.destructure(names, &value)?;
+                for (name, element) in names.iter().zip(values) {
+                    self.environment.borrow_mut().assign(name, element)?;
+                }
+                Ok(value)
+            },
+        }
+    }
+    fn execute_assert(&mut self, condition: &Expr, message: Option<&Expr>, line: usize) -> Result<(), Error> {
+        // A failed comparison shows both operands, so they are evaluated once here
+        // and compared as literals
+        let (passed, operands) = match condition {
+            Expr::Binary { left, operator, right, line } if matches!(operator.as_str(), "==" | "!=" | "<" | "<=" | ">" | ">=" | "in") => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                let comparison = Expr::Binary {
+                    left: Box::new(Expr::Literal { value: left.clone() }),
+                    operator: operator.clone(),
+                    right: Box::new(Expr::Literal { value: right.clone() }),
+                    line: *line,
+                };
+                (self.evaluate(&comparison)?, Some((left, right)))
+            },
+            _ => (self.evaluate(condition)?, None),
+        };
+        match passed {
+            LiteralValue::Boolean(true) => Ok(()),
+            LiteralValue::Boolean(false) => {
+                let mut report = format!("Assertion failed on line {}", line);
+                if let Some(message) = message {
+                    let message = self.evaluate(message)?;
+                    report.push_str(&format!(": {}", message));
+                }
+                if let Some((left, right)) = operands {
+                    report.push_str(&format!("\n  left:  {}\n  right: {}", left.repr(), right.repr()));
+                }
+                Err(self.error_manager.borrow_mut().report_runtime_error(&report))
+            },
+            other => Err(self.error_manager.borrow_mut().report_runtime_error(
+                &format!("Assertion on line {} must be a boolean, got {}.", line, other.repr()))),
         }
     }
+
     pub fn execute_var_declaration(&mut self, name: &str, initializer: Option<&Expr>) -> Result<(), Error> {
         let value = if let Some(expr) = initializer {
             self.evaluate(expr)?
         } else {
             LiteralValue::Nil // Default value if no initializer is provided
         };
-        self.environment.define(name.to_string(), value);
+        self.environment.borrow_mut().define(name.to_string(), value);
+        Ok(())
+    }
+
+    // Puts named arguments in the slot of the parameter with that name
+    fn bind_named(&mut self, what: &str, parameters: &[String], arguments: Vec<LiteralValue>, named: Vec<(String, LiteralValue)>) -> Result<Vec<LiteralValue>, Error> {
+        if named.is_empty() || arguments.len() > parameters.len() {
+            return Ok(arguments);
+        }
+        let mut slots: Vec<Option<LiteralValue>> = arguments.into_iter().map(Some).collect();
+        slots.resize(parameters.len(), None);
+        for (name, value) in named {
+            let Some(index) = parameters.iter().position(|parameter| *parameter == name) else {
+                return Err(self.error_manager.borrow_mut().report_runtime_error(
+                    &format!("{} has no parameter named '{}'.", what, name)));
+            };
+            if slots[index].is_some() {
+                return Err(self.error_manager.borrow_mut().report_runtime_error(
+                    &format!("{} got argument '{}' twice.", what, name)));
+            }
+            slots[index] = Some(value);
+        }
+        let mut bound = Vec::with_capacity(slots.len());
+        for (parameter, slot) in parameters.iter().zip(slots) {
+            match slot {
+                Some(value) => bound.push(value),
+                None => return Err(self.error_manager.borrow_mut().report_runtime_error(
+                    &format!("{} is missing argument '{}'.", what, parameter))),
+            }
+        }
+        Ok(bound)
+    }
+
+    // Natives that opt in get their named arguments as a trailing map
+    fn native_arguments(&mut self, native: &NativeFunction, mut arguments: Vec<LiteralValue>, named: Vec<(String, LiteralValue)>) -> Result<Vec<LiteralValue>, Error> {
+        if !native.named_arguments {
+            if let Some((name, _)) = named.first() {
+                return Err(self.error_manager.borrow_mut().report_runtime_error(
+                    &format!("Function '{}' does not take named arguments, got '{}'.", native.name, name)));
+            }
+            return Ok(arguments);
+        }
+        let map = LoxMap::new();
+        for (name, value) in named {
+            let _ = map.insert(LiteralValue::String(name), value);
+        }
+        arguments.push(LiteralValue::Map(Rc::new(map)));
+        Ok(arguments)
+    }
+
+    fn call(&mut self, callee: LiteralValue, arguments: Vec<LiteralValue>, named: Vec<(String, LiteralValue)>) -> Result<LiteralValue, Error> {
+        match callee {
+            LiteralValue::RecordType(record_type) => {
+                let arguments = self.bind_named(&format!("Record '{}'", record_type.name), &record_type.fields, arguments, named)?;
+                if arguments.len() != record_type.fields.len() {
+                    return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Record '{}' expects {} arguments but got {}.",
+                            record_type.name, record_type.fields.len(), arguments.len())));
+                }
+                Ok(LiteralValue::Record(Rc::new(RecordInstance::new(record_type, arguments))))
+            },
+            LiteralValue::VariantConstructor(variant) => {
+                let arguments = self.bind_named(&format!("Variant '{}.{}'", variant.enum_name, variant.name), &variant.fields, arguments, named)?;
+                if arguments.len() != variant.fields.len() {
+                    return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Variant '{}.{}' expects {} arguments but got {}.",
+                            variant.enum_name, variant.name, variant.fields.len(), arguments.len())));
+                }
+                Ok(LiteralValue::Variant(Rc::new(VariantValue::new(variant, arguments))))
+            },
+            LiteralValue::Function(function) => {
+                let arguments = self.bind_named(&format!("Function '{}'", function.name), &function.parameters, arguments, named)?;
+                if arguments.len() != function.arity() {
+                    return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Function '{}' expects {} arguments but got {}.",
+                            function.name, function.arity(), arguments.len())));
+                }
+                let mut call_env = Environment::new(self.error_manager.clone());
+                call_env.enclosing = Some(function.closure.clone());
+                for (parameter, argument) in function.parameters.iter().zip(arguments) {
+                    call_env.define(parameter.clone(), argument);
+                }
+                match self.execute_block_in(function.body.clone(), Rc::new(RefCell::new(call_env))) {
+                    Ok(()) => Ok(LiteralValue::Nil),
+                    Err(Error::Return(value)) => Ok(value),
+                    Err(e) => Err(e),
+                }
+            },
+            LiteralValue::Native(native) => {
+                let count = arguments.len();
+                let arguments = self.native_arguments(&native, arguments, named)?;
+                if !native.arity.contains(&count) {
+                    return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Function '{}' expects {} arguments but got {}.",
+                            native.name, native.arity_description(), count)));
+                }
+                (native.function)(self, arguments)
+            },
+            LiteralValue::BoundMethod(bound) => {
+                let count = arguments.len();
+                let mut method_arguments = Vec::with_capacity(count + 2);
+                method_arguments.push(bound.receiver.clone());
+                method_arguments.extend(arguments);
+                let method_arguments = self.native_arguments(&bound.method, method_arguments, named)?;
+                if !bound.method.arity.contains(&count) {
+                    return Err(self.error_manager.borrow_mut().report_runtime_error(
+                        &format!("Method '{}' expects {} arguments but got {}.",
+                            bound.method.name, bound.method.arity_description(), count)));
+                }
+                (bound.method.function)(self, method_arguments)
+            },
+            _ => Err(self.error_manager.borrow_mut().report_runtime_error(
+                &format!("Can only call functions, records and enum variants, got {}.", callee.repr()))),
+        }
+    }
+
+    fn expect_integer(&mut self, value: &LiteralValue, what: &str) -> Result<i64, Error> {
+        match value {
+            LiteralValue::Number(n) => builtins::as_integer(*n).ok_or_else(|| self.error_manager.borrow_mut().report_runtime_error(
+                &format!("{} must be an integer between -2^63 and 2^63, got {}.", what, value.repr()))),
+            _ => Err(self.error_manager.borrow_mut().report_runtime_error(
+                &format!("{} must be an integer, got {}.", what, value.repr()))),
+        }
+    }
+
+    // `needle in haystack`: set membership, map key, tuple element or substring
+    fn contains(&mut self, haystack: &LiteralValue, needle: &LiteralValue) -> Result<LiteralValue, Error> {
+        match (haystack, needle) {
+            (LiteralValue::Set(set), _) => Ok(LiteralValue::Boolean(set.contains(needle))),
+            (LiteralValue::Map(map), _) => Ok(LiteralValue::Boolean(map.contains_key(needle))),
+            (LiteralValue::Tuple(elements), _) => Ok(LiteralValue::Boolean(elements.contains(needle))),
+            (LiteralValue::String(s), LiteralValue::String(sub)) => Ok(LiteralValue::Boolean(s.contains(sub.as_str()))),
+            _ => Err(self.error_manager.borrow_mut().report_runtime_error(
+                &format!("Operator 'in' is not supported for {} in {}.", needle.repr(), haystack.repr()))),
+        }
+    }
+
+    // Splits a tuple into as many values as there are names
+    fn destructure(&mut self, names: &[String], value: &LiteralValue) -> Result<Vec<LiteralValue>, Error> {
+        match value {
+            LiteralValue::Tuple(elements) if elements.len() == names.len() => Ok(elements.clone()),
+            LiteralValue::Tuple(elements) => Err(self.error_manager.borrow_mut().report_runtime_error(
+                &format!("Cannot destructure a tuple of {} elements into {} names.", elements.len(), names.len()))),
+            _ => Err(self.error_manager.borrow_mut().report_runtime_error(
+                &format!("Only tuples can be destructured, got {}.", value.repr()))),
+        }
+    }
+
+    pub fn execute_const_declaration(&mut self, name: &str, initializer: &Expr) -> Result<(), Error> {
+        let value = self.evaluate(initializer)?;
+        self.environment.borrow_mut().define_constant(name.to_string(), value);
         Ok(())
     }
 
@@ -174,10 +590,19 @@ impl Interpreter {
             },
             Stmt::Print(expr) => {
                 let value = self.evaluate(&expr)?;
-                println!("{:?}", value);
+                println!("{}", value);
             },
+            Stmt::Assert { condition, message, line } => {
+                self.execute_assert(&condition, message.as_ref(), line)?;
+            },
+            Stmt::Defer { statement, .. } => match self.deferred.last_mut() {
+                Some(frame) => frame.push(*statement),
+                None => return Err(self.error_manager.borrow_mut().report_runtime_error(
+                    "Cannot use 'defer' outside of a block.")),
+            },
+            // Only `lox test` runs test blocks
+            Stmt::Test { .. } => {},
             Stmt::If {condition, then_branch, else_branch} => {
-                println!("Evaluating if statement with condition: {:?}", condition);
                 let condition_value = self.evaluate(&condition)?;
                 if let LiteralValue::Boolean(true) = condition_value {
                     self.interpret_single_statement(*then_branch)?;
@@ -185,140 +610,156 @@ impl Interpreter {
                     self.interpret_single_statement(*else_branch)?;
                 }
             },
-            Stmt::Var { name, initializer } => {
+            Stmt::Var { name, initializer, .. } => {
                 self.execute_var_declaration(&name, initializer.as_ref())?;
             },
+            Stmt::Const { name, initializer, .. } => {
+                self.execute_const_declaration(&name, &initializer)?;
+            },
+            Stmt::Record { name, fields, .. } => {
+                let record_type = RecordType::new(name.clone(), fields);
+                self.environment.borrow_mut().define_constant(name, LiteralValue::RecordType(Rc::new(record_type)));
+            },
+            Stmt::Function { name, parameters, body, .. } => {
+                let parameters = parameters.into_iter().map(|(parameter, _)| parameter).collect();
+                let function = LoxFunction::new(name.clone(), parameters, body, self.environment.clone());
+                self.environment.borrow_mut().define(name, LiteralValue::Function(Rc::new(function)));
+            },
+            Stmt::Return { value, .. } => {
+                let value = match value {
+                    Some(value) => self.evaluate(&value)?,
+                    None => LiteralValue::Nil,
+                };
+                return Err(Error::Return(value));
+            },
+            Stmt::Enum { name, variants, .. } => {
+                let enum_type = EnumType::new(name.clone(), variants);
+                self.environment.borrow_mut().define_constant(name, LiteralValue::Enum(Rc::new(enum_type)));
+            },
+            Stmt::Destructure { names, initializer, .. } => {
+                let value = self.evaluate(&initializer)?;
+                let values = self.destructure(&names, &value)?;
+                for (name, element) in names.into_iter().zip(values) {
+                    self.environment.borrow_mut().define(name, element);
+                }
+            },
             Stmt::Block(statements) => {
                 self.execute_bock(statements)?;
             },
-            Stmt::While {condition,body} => {
-                let condition_value = match self.evaluate(&condition) {
-                    Ok(value) => {
-                        println!("Evaluation successful: {:?}", value);
-                        value
-                    },
-                    Err(e) => {
-                        println!("Evaluation failed with error: {:?}", e);
-                        return Err(e);
+            Stmt::While { condition, body, increment, label } => {
+                while let LiteralValue::Boolean(true) = self.evaluate(&condition)? {
+                    if !self.execute_loop_body(&body, &label)? {
+                        break;
+                    }
+                    if let Some(increment) = &increment {
+                        self.evaluate(increment)?;
                     }
-                };
-                //println!("Condition value: {:?}", condition_value);
-                if let LiteralValue::Boolean(true) = condition_value {
-                    self.interpret_single_statement(*body)?;
                 }
             },
-            Stmt::Sequence(statements) => {
-                for stmt in statements {
-                    self.interpret_single_statement(stmt)?;
+            Stmt::DoWhile { body, condition, label } => {
+                loop {
+                    if !self.execute_loop_body(&body, &label)? {
+                        break;
+                    }
+                    if let LiteralValue::Boolean(true) = self.evaluate(&condition)? {
+                        continue;
+                    }
+                    break;
                 }
             },
+            Stmt::Break { label, .. } => return Err(Error::Break(label)),
+            Stmt::Continue { label, .. } => return Err(Error::Continue(label)),
+            // Braced `if`/loop bodies share the enclosing scope but keep their own defers
+            Stmt::Sequence(statements) => {
+                self.execute_with_defers(statements)?;
+            },
             _ => return Err(self.error_manager.borrow_mut().report_runtime_error(
                 &format!("Unsupported statement type: {:?}", statement))),
         }
         Ok(LiteralValue::Nil) // Return nil if no value is produced
     }
 
+    // Runs one iteration of a loop body and absorbs the `break`/`continue`
+    // signals aimed at this loop. Returns false when the loop must stop.
+    fn execute_loop_body(&mut self, body: &Stmt, label: &Option<String>) -> Result<bool, Error> {
+        match self.interpret_single_statement(body.clone()) {
+            Ok(_) => Ok(true),
+            Err(Error::Break(target)) if target.is_none() || target == *label => Ok(false),
+            Err(Error::Continue(target)) if target.is_none() || target == *label => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+
     fn execute_bock(&mut self, statements_list: Vec<Stmt>) -> Result<(), Error> {
+        // Create a new environment enclosed by the current one
+        let mut block_env = Environment::new(self.error_manager.clone());
+        block_env.enclosing = Some(self.environment.clone());
+        self.execute_block_in(statements_list, Rc::new(RefCell::new(block_env)))
+    }
+
+    // Runs statements in the given environment, e.g. a function call's scope
+    fn execute_block_in(&mut self, statements_list: Vec<Stmt>, environment: Rc<RefCell<Environment>>) -> Result<(), Error> {
         // Save the current environment
-        let previous_env = Rc::new(RefCell::new(self.environment.clone()));
+        let previous_env = std::mem::replace(&mut self.environment, environment);
+        let result = self.execute_with_defers(statements_list);
 
-    
-        // Create a new environment enclosed by the previous one
-        self.environment = Environment::new(self.error_manager.clone());
-        self.environment.enclosing = Some(previous_env.clone());
+        // Restore previous environment after block
+        self.environment = previous_env;
+        result
+    }
+
+    // Runs statements with a fresh frame for the `defer`s among them
+    fn execute_with_defers(&mut self, statements_list: Vec<Stmt>) -> Result<(), Error> {
+        self.deferred.push(Vec::new());
 
+        let mut result = Ok(());
         for statement in statements_list {
             //println!("Currently working on statement: {:?}", statement);
             if let Err(e) = self.interpret_single_statement(statement) {
-                // Optional: print debug info
-                println!("Runtime error: {:?}", e);
-    
-                // Restore the previous environment
-                let previous = self.environment.enclosing.as_ref().unwrap().borrow().clone();
-                self.environment = previous;
-    
-                // Return the error
-                return Err(e);
+                result = Err(e);
+                break;
             }
         }
-    
-        // Restore previous environment after block
-        self.environment = previous_env.as_ref().borrow().clone();
-        Ok(())
+
+        // Deferred statements run last in first out, however the body was left.
+        // They all run; the first error, from the body or from them, is kept.
+        let deferred = self.deferred.pop().unwrap_or_default();
+        for statement in deferred.into_iter().rev() {
+            if let Err(e) = self.interpret_single_statement(statement) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
     }
     
     
+    // Runs `eval`'d code in a child of the current scope. A trailing expression
+    // statement is the result, anything else gives nil.
+    pub fn interpret_in_child_scope(&mut self, mut statements_list: Vec<Stmt>) -> Result<LiteralValue, Error> {
+        let result_expression = match statements_list.last() {
+            Some(Stmt::Expression(_)) => match statements_list.pop() {
+                Some(Stmt::Expression(expr)) => Some(expr),
+                _ => None,
+            },
+            _ => None,
+        };
+        let mut child_env = Environment::new(self.error_manager.clone());
+        child_env.enclosing = Some(self.environment.clone());
+        let previous_env = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(child_env)));
+        let result = self.interpret(statements_list).and_then(|_| match &result_expression {
+            Some(expr) => self.evaluate(expr),
+            None => Ok(LiteralValue::Nil),
+        });
+        self.environment = previous_env;
+        result
+    }
+
     pub fn interpret(&mut self, statements_list: Vec<Stmt>) -> Result<LiteralValue, Error> {
         for statement in statements_list {
-            match statement {
-                Stmt::Expression(expr) => {
-                    self.evaluate(&expr)?;
-                },
-                Stmt::If {condition, then_branch, else_branch} => {
-                    //println!("Evaluating if statement with condition WITHIN INTERPRET FUNCTION: {:?}", condition);
-                    let condition_value = match self.evaluate(&condition) {
-                        Ok(value) => {
-                            println!("Evaluation successful: {:?}", value);
-                            value
-                        },
-                        Err(e) => {
-                            println!("Evaluation failed with error: {:?}", e);
-                            return Err(e);
-                        }
-                    };
-                    //println!("Condition value: {:?}", condition_value);
-                    if let LiteralValue::Boolean(true) = condition_value {
-                        self.interpret_single_statement(*then_branch)?;
-                    } else if let Some(else_branch) = else_branch {
-                        self.interpret_single_statement(*else_branch)?;
-                    }
-                },
-                Stmt::Print(expr) => {
-                    let value = self.evaluate(&expr)?;
-                    println!("{:?}", value);
-                },
-                Stmt::Var { name, initializer } => {
-                    self.execute_var_declaration(&name, initializer.as_ref())?;
-                },
-                Stmt::Block(statements) => {
-                    self.execute_bock(statements)?;
-                },
-                Stmt::While { condition, body }
-                => {
-                let condition_value = match self.evaluate(&condition) {
-                    Ok(value) => {
-                        println!("Evaluation successful: {:?}", value);
-                        value
-                    },
-                    Err(e) => {
-                        println!("Evaluation failed with error: {:?}", e);
-                        return Err(e);
-                    }
-                };
-                //println!("Condition value: {:?}", condition_value);
-                while let LiteralValue::Boolean(true) = condition_value {
-                    self.interpret_single_statement((*body).clone())?;
-                    // Re-evaluate the condition for the next iteration
-                    let condition_value = match self.evaluate(&condition) {
-                        Ok(value) => {
-                            //println!("Re-evaluation successful: {:?}", value);
-                            value
-                        },
-                        Err(e) => {
-                            println!("Re-evaluation failed with error: {:?}", e);
-                            return Err(e);
-                        }
-                    };
-                    //println!("Condition value after re-evaluation: {:?}", condition_value);
-                    if let LiteralValue::Boolean(false) = condition_value {
-                        break; // Exit the loop if the condition is false
-                    }
-                }
-                },
-                _ => return Err(self.error_manager.borrow_mut().report_runtime_error(
-                    &format!("Unsupported statement type: {:?}", statement))),
-            }
+            self.interpret_single_statement(statement)?;
         }
         Ok(LiteralValue::Nil) // Return nil if no value is produced
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,397 @@
+use crate::lox::map::LoxMap;
+use crate::tool::generate_ast::LiteralValue;
+use std::fmt;
+use std::rc::Rc;
+
+// JSON conversion for `json_parse` and `json_stringify`.
+// Arrays become tuples, objects become maps with string keys and null is nil.
+
+// Deeper documents are refused instead of overflowing the Rust stack
+const MAX_DEPTH: usize = 512;
+
+#[derive(Debug)]
+pub struct JsonError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.line, self.column)
+    }
+}
+
+pub fn parse(text: &str) -> Result<LiteralValue, JsonError> {
+    let mut parser = JsonParser { chars: text.chars().collect(), current: 0, line: 1, column: 1 };
+    parser.skip_whitespace();
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if !parser.is_at_end() {
+        return Err(parser.error("Unexpected data after the JSON value"));
+    }
+    Ok(value)
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    current: usize,
+    line: usize,
+    column: usize,
+}
+
+impl JsonParser {
+    fn is_at_end(&self) -> bool {
+        self.current >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.current).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.current += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> JsonError {
+        JsonError { message: message.to_string(), line: self.line, column: self.column }
+    }
+
+    fn unexpected(&self) -> JsonError {
+        match self.peek() {
+            Some(c) => self.error(&format!("Unexpected character '{}'", c.escape_debug())),
+            None => self.error("Unexpected end of input"),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        if self.peek() == Some(expected) {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.advance();
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<LiteralValue, JsonError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("Nesting is too deep"));
+        }
+        match self.peek() {
+            Some('{') => self.object(depth),
+            Some('[') => self.array(depth),
+            Some('"') => Ok(LiteralValue::String(self.string()?)),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.keyword("true", LiteralValue::Boolean(true)),
+            Some('f') => self.keyword("false", LiteralValue::Boolean(false)),
+            Some('n') => self.keyword("null", LiteralValue::Nil),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: LiteralValue) -> Result<LiteralValue, JsonError> {
+        for expected in word.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    fn object(&mut self, depth: usize) -> Result<LiteralValue, JsonError> {
+        self.expect('{')?;
+        let map = LoxMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.advance();
+            return Ok(LiteralValue::Map(Rc::new(map)));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected a string key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+            let value = self.value(depth + 1)?;
+            // Later duplicates win, like most JSON implementations
+            let _ = map.insert(LiteralValue::String(key), value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => { self.advance(); },
+                Some('}') => {
+                    self.advance();
+                    return Ok(LiteralValue::Map(Rc::new(map)));
+                },
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<LiteralValue, JsonError> {
+        self.expect('[')?;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.advance();
+            return Ok(LiteralValue::Tuple(elements));
+        }
+        loop {
+            self.skip_whitespace();
+            elements.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => { self.advance(); },
+                Some(']') => {
+                    self.advance();
+                    return Ok(LiteralValue::Tuple(elements));
+                },
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn digits(&mut self, text: &mut String) -> Result<(), JsonError> {
+        if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            return Err(self.unexpected());
+        }
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_digit()) {
+            text.push(c);
+            self.advance();
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<LiteralValue, JsonError> {
+        let mut text = String::new();
+        if self.peek() == Some('-') {
+            text.push('-');
+            self.advance();
+        }
+        // No leading zeros: `0` must stand alone before the fraction
+        if self.peek() == Some('0') {
+            text.push('0');
+            self.advance();
+        } else {
+            self.digits(&mut text)?;
+        }
+        if self.peek() == Some('.') {
+            text.push('.');
+            self.advance();
+            self.digits(&mut text)?;
+        }
+        if let Some(e @ ('e' | 'E')) = self.peek() {
+            text.push(e);
+            self.advance();
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                text.push(sign);
+                self.advance();
+            }
+            self.digits(&mut text)?;
+        }
+        // The grammar above only lets valid float syntax through
+        Ok(LiteralValue::Number(text.parse().unwrap_or(f64::NAN)))
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut result = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("Unterminated string")),
+                Some('"') => {
+                    self.advance();
+                    return Ok(result);
+                },
+                Some('\\') => {
+                    self.advance();
+                    result.push(self.escape()?);
+                },
+                Some(c) if (c as u32) < 0x20 => return Err(self.error("Control character in string")),
+                Some(c) => {
+                    result.push(c);
+                    self.advance();
+                },
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, JsonError> {
+        let c = match self.peek() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.advance();
+                return self.unicode_escape();
+            },
+            _ => return Err(self.error("Invalid escape sequence")),
+        };
+        self.advance();
+        Ok(c)
+    }
+
+    // \uXXXX, where a high surrogate must be followed by an escaped low one
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.peek() != Some('\\') || self.chars.get(self.current + 1) != Some(&'u') {
+                return Err(self.error("Unpaired surrogate in \\u escape"));
+            }
+            self.advance();
+            self.advance();
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("Unpaired surrogate in \\u escape"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("Unpaired surrogate in \\u escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) else {
+                return Err(self.error("Invalid \\u escape"));
+            };
+            code = code * 16 + digit;
+            self.advance();
+        }
+        Ok(code)
+    }
+}
+
+// `indent` of 0 gives compact output, anything else pretty-prints with
+// that many spaces per level
+pub fn stringify(value: &LiteralValue, indent: usize) -> Result<String, String> {
+    let mut writer = JsonWriter { output: String::new(), indent, open_maps: Vec::new() };
+    writer.value(value, 0)?;
+    Ok(writer.output)
+}
+
+struct JsonWriter {
+    output: String,
+    indent: usize,
+    // Maps on the path from the root, a repeat means the value is cyclic
+    open_maps: Vec<*const LoxMap>,
+}
+
+impl JsonWriter {
+    fn value(&mut self, value: &LiteralValue, depth: usize) -> Result<(), String> {
+        match value {
+            LiteralValue::Nil => self.output.push_str("null"),
+            LiteralValue::Boolean(b) => self.output.push_str(if *b { "true" } else { "false" }),
+            LiteralValue::Number(n) if n.is_finite() => self.output.push_str(&n.to_string()),
+            LiteralValue::Number(n) => return Err(format!("{} has no JSON representation", n)),
+            LiteralValue::String(s) => self.string(s),
+            LiteralValue::Tuple(elements) => self.array(elements, depth)?,
+            LiteralValue::Set(set) => self.array(set.items(), depth)?,
+            LiteralValue::Record(record) => {
+                let fields: Vec<(String, LiteralValue)> = record.record_type.fields.iter().cloned()
+                    .zip(record.values.iter().cloned())
+                    .collect();
+                self.object(&fields, depth)?;
+            },
+            LiteralValue::Map(map) => {
+                let pointer = Rc::as_ptr(map);
+                if self.open_maps.contains(&pointer) {
+                    return Err("cannot serialize a map that contains itself".to_string());
+                }
+                let mut fields = Vec::with_capacity(map.len());
+                for (key, value) in map.entries() {
+                    match key {
+                        LiteralValue::String(key) => fields.push((key, value)),
+                        other => return Err(format!("object keys must be strings, got {}", other.repr())),
+                    }
+                }
+                self.open_maps.push(pointer);
+                let result = self.object(&fields, depth);
+                self.open_maps.pop();
+                result?;
+            },
+            other => return Err(format!("{} has no JSON representation", other.repr())),
+        }
+        Ok(())
+    }
+
+    fn newline(&mut self, depth: usize) {
+        if self.indent > 0 {
+            self.output.push('\n');
+            self.output.push_str(&" ".repeat(self.indent * depth));
+        }
+    }
+
+    fn array(&mut self, elements: &[LiteralValue], depth: usize) -> Result<(), String> {
+        self.output.push('[');
+        for (position, element) in elements.iter().enumerate() {
+            if position > 0 {
+                self.output.push(',');
+            }
+            self.newline(depth + 1);
+            self.value(element, depth + 1)?;
+        }
+        if !elements.is_empty() {
+            self.newline(depth);
+        }
+        self.output.push(']');
+        Ok(())
+    }
+
+    fn object(&mut self, fields: &[(String, LiteralValue)], depth: usize) -> Result<(), String> {
+        self.output.push('{');
+        for (position, (key, value)) in fields.iter().enumerate() {
+            if position > 0 {
+                self.output.push(',');
+            }
+            self.newline(depth + 1);
+            self.string(key);
+            self.output.push_str(if self.indent > 0 { ": " } else { ":" });
+            self.value(value, depth + 1)?;
+        }
+        if !fields.is_empty() {
+            self.newline(depth);
+        }
+        self.output.push('}');
+        Ok(())
+    }
+
+    fn string(&mut self, s: &str) {
+        self.output.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                '\u{8}' => self.output.push_str("\\b"),
+                '\u{c}' => self.output.push_str("\\f"),
+                c if (c as u32) < 0x20 => self.output.push_str(&format!("\\u{:04x}", c as u32)),
+                c => self.output.push(c),
+            }
+        }
+        self.output.push('"');
+    }
+}
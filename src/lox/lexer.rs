@@ -1,40 +1,287 @@
 use std::io::BufRead;
 use std::fs;
 use std::io::Write;
-use crate::lox::error_manager::ErrorManager;
+use crate::lox::error_manager::{Error, ErrorManager};
 use crate::lox::scanner;
-use crate::lox::parser::Parser;
 use crate::lox::interpreter::Interpreter;
+use crate::lox::resolver::Resolver;
+use crate::lox::type_checker::TypeChecker;
+use crate::lox::doc_generator::{self, DocFormat};
+use crate::lox::permissions::Permissions;
+use crate::lox::rng::Rng;
+use crate::tool::generate_ast::{LiteralValue, Stmt};
 use std::rc::Rc;
 use std::cell::RefCell;
 
 pub struct Lexer {
     error_reporter: Rc<RefCell<ErrorManager>>,
+    permissions: Permissions,
+    seed: Option<u64>,
+    // Everything after the script path, exposed to the script as `args`
+    script_args: Vec<String>,
 }
 
 impl Lexer {
     pub fn new() -> Self {
-        Self { error_reporter: Rc::new(RefCell::new(ErrorManager::new())) }
+        Self { error_reporter: Rc::new(RefCell::new(ErrorManager::new())), permissions: Permissions::new(), seed: None, script_args: Vec::new() }
     }
 
     pub fn main(&mut self) {
-        let args: Vec<String> = std::env::args().collect();
+        let mut args: Vec<String> = std::env::args().collect();
+        if args.get(1).is_some_and(|arg| arg == "check") {
+            return self.check_command(&args[2..]);
+        }
+        if args.get(1).is_some_and(|arg| arg == "test") {
+            return self.test_command(&args[2..]);
+        }
+        if args.get(1).is_some_and(|arg| arg == "doc") {
+            return self.doc_command(&args[2..]);
+        }
+
+        // Options come before the script path, anything after it belongs to the script
+        while args.len() > 1 && args[1].starts_with("--") {
+            let option = args.remove(1);
+            self.parse_option(&option);
+        }
 
         match args.len() {
             1 => self.run_prompt(),
-            2 => self.run_file(&args[1]),
             _ => {
-                eprintln!("Usage: lox [script]");
-                std::process::exit(64);
+                self.script_args = args[2..].to_vec();
+                self.run_file(&args[1]);
             }
         }
 
     }
 
+    fn usage() -> ! {
+        eprintln!("Usage: lox [--allow-fs=<dir>]... [--allow-run] [--seed=<n>] [script [args...]]");
+        eprintln!("       lox check [--types] <script>");
+        eprintln!("       lox test [options] [paths...]");
+        eprintln!("       lox doc [--html] [--out=<dir>] <paths...>");
+        std::process::exit(64);
+    }
+
+    fn parse_option(&mut self, option: &str) {
+        if let Some(dir) = option.strip_prefix("--allow-fs=") {
+            if let Err(message) = self.permissions.allow_fs(dir) {
+                eprintln!("{}", message);
+                std::process::exit(64);
+            }
+        } else if option == "--allow-run" {
+            self.permissions.allow_run = true;
+        } else if let Some(seed) = option.strip_prefix("--seed=") {
+            match seed.parse::<i64>() {
+                Ok(seed) => self.seed = Some(seed as u64),
+                Err(_) => {
+                    eprintln!("--seed expects an integer, got '{}'.", seed);
+                    std::process::exit(64);
+                }
+            }
+        } else {
+            eprintln!("Unknown option '{}'.", option);
+            Self::usage();
+        }
+    }
+
     fn run_file(&mut self, path: &str) {
         let bytes = fs::read(path).expect("Failed to read file");
         let source = String::from_utf8(bytes).expect("Invalid UTF-8 in file");
         self.run(source);
+        if self.error_reporter.borrow().had_runtime_error {
+            std::process::exit(70);
+        }
+    }
+
+    // lox check [--types] <script>: static checks only, nothing is executed
+    fn check_command(&mut self, args: &[String]) {
+        let check_types = args.iter().any(|arg| arg == "--types");
+        let paths: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+        if paths.len() != 1 || args.iter().any(|arg| arg.starts_with("--") && arg != "--types") {
+            eprintln!("Usage: lox check [--types] <script>");
+            std::process::exit(64);
+        }
+        let bytes = fs::read(paths[0]).expect("Failed to read file");
+        let source = String::from_utf8(bytes).expect("Invalid UTF-8 in file");
+
+        let error_reporter = self.error_reporter.clone();
+        let tokens = scanner::Scanner::new(source, error_reporter.clone()).scan_tokens();
+        let statements = crate::lox::parser::Parser::new(tokens, error_reporter.clone()).parse();
+        Resolver::new(error_reporter.clone()).resolve(&statements);
+        if check_types && !error_reporter.borrow().had_error {
+            TypeChecker::new(error_reporter.clone()).check(&statements);
+        }
+        if error_reporter.borrow().had_error {
+            std::process::exit(65);
+        }
+        println!("No errors found in {}.", paths[0]);
+    }
+
+    // lox doc [--html] [--out=<dir>] <paths...>: one reference page per script,
+    // printed to stdout or written to <dir>/<script>.md (or .html)
+    fn doc_command(&mut self, args: &[String]) {
+        let mut format = DocFormat::Markdown;
+        let mut out_dir = None;
+        let mut paths = Vec::new();
+        for arg in args {
+            if arg == "--html" {
+                format = DocFormat::Html;
+            } else if let Some(dir) = arg.strip_prefix("--out=") {
+                out_dir = Some(std::path::PathBuf::from(dir));
+            } else if arg.starts_with("--") {
+                eprintln!("Unknown option '{}'.", arg);
+                Self::usage();
+            } else {
+                paths.push(std::path::PathBuf::from(arg));
+            }
+        }
+        if paths.is_empty() {
+            Self::usage();
+        }
+        let mut files = Vec::new();
+        for path in &paths {
+            if let Err(e) = Self::collect_lox_files(path, &mut files) {
+                eprintln!("Cannot read '{}': {}", path.display(), e);
+                std::process::exit(64);
+            }
+        }
+
+        for file in &files {
+            let bytes = fs::read(file).expect("Failed to read file");
+            let source = String::from_utf8(bytes).expect("Invalid UTF-8 in file");
+            let error_reporter = self.error_reporter.clone();
+            let tokens = scanner::Scanner::new(source, error_reporter.clone()).scan_tokens();
+            let statements = crate::lox::parser::Parser::new(tokens, error_reporter.clone()).parse();
+            if error_reporter.borrow().had_error {
+                std::process::exit(65);
+            }
+            let title = file.file_stem().map_or_else(|| file.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+            let page = doc_generator::render(&title, &statements, format);
+            match &out_dir {
+                Some(dir) => {
+                    let target = dir.join(format!("{}.{}", title, format.extension()));
+                    if let Err(e) = fs::create_dir_all(dir).and_then(|_| fs::write(&target, page)) {
+                        eprintln!("Cannot write '{}': {}", target.display(), e);
+                        std::process::exit(74);
+                    }
+                    println!("Wrote {}", target.display());
+                },
+                None => println!("{}", page),
+            }
+        }
+    }
+
+    // lox test [options] [paths...]: runs every `test "name" { }` block found in
+    // the given files and directories (the current directory by default).
+    // Each test gets a fresh interpreter that first runs the file's top-level code.
+    fn test_command(&mut self, args: &[String]) {
+        let mut paths = Vec::new();
+        for arg in args {
+            if arg.starts_with("--") {
+                self.parse_option(arg);
+            } else {
+                paths.push(std::path::PathBuf::from(arg));
+            }
+        }
+        if paths.is_empty() {
+            paths.push(std::path::PathBuf::from("."));
+        }
+        let mut files = Vec::new();
+        for path in &paths {
+            if let Err(e) = Self::collect_lox_files(path, &mut files) {
+                eprintln!("Cannot read '{}': {}", path.display(), e);
+                std::process::exit(64);
+            }
+        }
+
+        let (mut passed, mut failed) = (0, 0);
+        for file in &files {
+            let (file_passed, file_failed) = self.run_tests_in(file);
+            passed += file_passed;
+            failed += file_failed;
+        }
+        println!();
+        println!("{} passed, {} failed", passed, failed);
+        if failed > 0 {
+            std::process::exit(1);
+        }
+    }
+
+    fn collect_lox_files(path: &std::path::Path, files: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+        if !path.is_dir() {
+            fs::metadata(path)?;
+            files.push(path.to_path_buf());
+            return Ok(());
+        }
+        let mut entries = fs::read_dir(path)?.map(|entry| entry.map(|e| e.path())).collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().is_some_and(|extension| extension == "lox") {
+                Self::collect_lox_files(&entry, files)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Returns the (passed, failed) counts; a file that doesn't compile counts as one failure
+    fn run_tests_in(&mut self, path: &std::path::Path) -> (usize, usize) {
+        let source = match fs::read(path).map(String::from_utf8) {
+            Ok(Ok(source)) => source,
+            _ => {
+                println!("FAIL {}: cannot read the file as UTF-8", path.display());
+                return (0, 1);
+            }
+        };
+        let error_reporter = Rc::new(RefCell::new(ErrorManager::new()));
+        let tokens = scanner::Scanner::new(source, error_reporter.clone()).scan_tokens();
+        let statements = crate::lox::parser::Parser::new(tokens, error_reporter.clone()).parse();
+        Resolver::new(error_reporter.clone()).resolve(&statements);
+        if error_reporter.borrow().had_error {
+            println!("FAIL {}: errors during parsing", path.display());
+            return (0, 1);
+        }
+
+        let (tests, setup): (Vec<Stmt>, Vec<Stmt>) = statements.into_iter()
+            .partition(|statement| matches!(statement, Stmt::Test { .. }));
+        if tests.is_empty() {
+            return (0, 0);
+        }
+        println!("{}", path.display());
+        let (mut passed, mut failed) = (0, 0);
+        for test in tests {
+            let Stmt::Test { name, body, .. } = test else { continue };
+            // The failure is reported below, under the test's name
+            let error_reporter = Rc::new(RefCell::new(ErrorManager::new()));
+            error_reporter.borrow_mut().silent = true;
+            let mut interpreter = Interpreter::new(error_reporter.clone());
+            interpreter.permissions = self.permissions.clone();
+            if let Some(seed) = self.seed {
+                interpreter.rng = Rng::seeded(seed);
+            }
+            let mut program = setup.clone();
+            program.push(Stmt::Block(body));
+            let outcome = match interpreter.interpret(program) {
+                Ok(_) => None,
+                Err(Error::Exit(0)) => None,
+                Err(Error::Exit(code)) => Some(format!("exited with status {}", code)),
+                Err(Error::Runtime(message)) | Err(Error::Other(message)) => Some(message),
+                Err(other) => Some(format!("{:?}", other)),
+            };
+            match outcome {
+                None => {
+                    println!("  ok    {}", name);
+                    passed += 1;
+                },
+                Some(message) => {
+                    println!("  FAIL  {}", name);
+                    for line in message.lines() {
+                        println!("        {}", line);
+                    }
+                    failed += 1;
+                },
+            }
+        }
+        (passed, failed)
     }
 
     fn run_prompt(&mut self) {
@@ -64,22 +311,38 @@ impl Lexer {
 
     fn run(&mut self, source: String) {
         self.error_reporter.borrow_mut().had_error = false; // Reset error state
+        self.error_reporter.borrow_mut().had_runtime_error = false;
         // Here you would typically parse and interpret the source code.
         // For now, we just print it to demonstrate that it was read.
         //println!("Running Lox code:\n{}", source.clone());
-        let mut error_reporter = self.error_reporter.clone();
+        let error_reporter = self.error_reporter.clone();
         let mut scanner = scanner::Scanner::new(source.clone(), error_reporter.clone());
-        let mut tokens = scanner.scan_tokens();
+        let tokens = scanner.scan_tokens();
         let mut parser = crate::lox::parser::Parser::new(tokens, error_reporter.clone());
         //println!("Starting parsing");
-        let mut statements = parser.parse();
+        let statements = parser.parse();
         //println!("Finished parsing");
-        let mut interpreter = Interpreter::new(error_reporter.clone());
-        interpreter.interpret(statements);
+        // Static checks run before anything executes
+        Resolver::new(error_reporter.clone()).resolve(&statements);
+        if !error_reporter.borrow().had_error {
+            let mut interpreter = Interpreter::new(error_reporter.clone());
+            interpreter.permissions = self.permissions.clone();
+            if let Some(seed) = self.seed {
+                interpreter.rng = Rng::seeded(seed);
+            }
+            let script_args = self.script_args.iter().map(|arg| LiteralValue::String(arg.clone())).collect();
+            interpreter.define_global("args", LiteralValue::Tuple(script_args));
+            if let Err(Error::Exit(code)) = interpreter.interpret(statements) {
+                std::io::stdout().flush().expect("Failed to flush stdout");
+                // process::exit skips destructors, the terminal must be restored first
+                drop(interpreter);
+                std::process::exit(code);
+            }
+        }
         if error_reporter.borrow_mut().had_error {
             eprintln!("Errors encountered during parsing or interpretation.");
             std::process::exit(65); // Exit with error code
-        } else {
+        } else if !error_reporter.borrow().had_runtime_error {
             println!("Lox code executed successfully.");
         }       
     }
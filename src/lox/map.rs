@@ -0,0 +1,123 @@
+use crate::lox::display::fmt_nested;
+use crate::lox::set::HashKey;
+use crate::tool::generate_ast::LiteralValue;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+// Mutable map from hashable keys to values, shared by reference: every copy
+// of a map value sees the same entries. Iteration follows insertion order.
+// Because a map can end up containing itself, equality is identity and
+// printing stops at a map that is already being printed.
+#[derive(Default)]
+pub struct LoxMap {
+    entries: RefCell<Vec<(LiteralValue, LiteralValue)>>,
+    index: RefCell<HashMap<HashKey, usize>>,
+}
+
+impl LoxMap {
+    pub fn new() -> Self {
+        LoxMap::default()
+    }
+
+    // Fails with the offending key when it is not hashable
+    pub fn insert(&self, key: LiteralValue, value: LiteralValue) -> Result<(), LiteralValue> {
+        let Some(hash_key) = HashKey::from_value(&key) else {
+            return Err(key);
+        };
+        let mut entries = self.entries.borrow_mut();
+        let mut index = self.index.borrow_mut();
+        match index.get(&hash_key) {
+            Some(&position) => entries[position].1 = value,
+            None => {
+                index.insert(hash_key, entries.len());
+                entries.push((key, value));
+            },
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &LiteralValue) -> Option<LiteralValue> {
+        let position = *self.index.borrow().get(&HashKey::from_value(key)?)?;
+        Some(self.entries.borrow()[position].1.clone())
+    }
+
+    pub fn contains_key(&self, key: &LiteralValue) -> bool {
+        HashKey::from_value(key).is_some_and(|key| self.index.borrow().contains_key(&key))
+    }
+
+    pub fn remove(&self, key: &LiteralValue) -> Option<LiteralValue> {
+        let position = self.index.borrow_mut().remove(&HashKey::from_value(key)?)?;
+        let (_, value) = self.entries.borrow_mut().remove(position);
+        // Entries after the removed one moved down by one
+        for slot in self.index.borrow_mut().values_mut() {
+            if *slot > position {
+                *slot -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    // Pairs with `len`, nothing needs it yet
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+
+    // Snapshot of the entries, safe to hold while the map is modified
+    pub fn entries(&self) -> Vec<(LiteralValue, LiteralValue)> {
+        self.entries.borrow().clone()
+    }
+}
+
+impl PartialEq for LoxMap {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+thread_local! {
+    // Maps currently being formatted, to cut cycles short
+    static FORMATTING: RefCell<Vec<*const LoxMap>> = const { RefCell::new(Vec::new()) };
+}
+
+impl LoxMap {
+    fn fmt_entries(&self, f: &mut fmt::Formatter, entry: fn(&mut fmt::Formatter, &LiteralValue) -> fmt::Result) -> fmt::Result {
+        let pointer = self as *const LoxMap;
+        if FORMATTING.with(|stack| stack.borrow().contains(&pointer)) {
+            return write!(f, "{{...}}");
+        }
+        FORMATTING.with(|stack| stack.borrow_mut().push(pointer));
+        let result = (|| {
+            write!(f, "{{")?;
+            for (position, (key, value)) in self.entries().iter().enumerate() {
+                if position > 0 {
+                    write!(f, ", ")?;
+                }
+                entry(f, key)?;
+                write!(f, ": ")?;
+                entry(f, value)?;
+            }
+            write!(f, "}}")
+        })();
+        FORMATTING.with(|stack| stack.borrow_mut().pop());
+        result
+    }
+}
+
+impl fmt::Debug for LoxMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_entries(f, |f, value| write!(f, "{:?}", value))
+    }
+}
+
+// Prints as `{"a": 1, (1, 2): nil}`
+impl fmt::Display for LoxMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_entries(f, fmt_nested)
+    }
+}
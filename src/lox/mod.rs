@@ -6,4 +6,25 @@ pub mod scanner;
 pub mod parser;
 pub mod interpreter;
 pub mod environment;
+pub mod resolver;
+pub mod record;
+pub mod enumeration;
+pub mod function;
+pub mod type_checker;
+pub mod doc_generator;
+pub mod native;
+pub mod set;
+pub mod map;
+pub mod json;
+pub mod argparse;
+pub mod clock;
+pub mod date;
+pub mod rng;
+pub mod terminal;
+pub mod slice;
+pub mod display;
+pub mod format;
+pub mod namespace;
+pub mod permissions;
+pub mod builtins;
 
@@ -0,0 +1,30 @@
+use crate::tool::generate_ast::LiteralValue;
+use std::collections::BTreeMap;
+use std::fmt;
+
+// A named group of built-ins reached through properties, e.g. `math.sqrt`
+#[derive(PartialEq)]
+pub struct Namespace {
+    pub name: String,
+    pub members: BTreeMap<String, LiteralValue>,
+}
+
+impl Namespace {
+    pub fn new(name: &str) -> Self {
+        Namespace { name: name.to_string(), members: BTreeMap::new() }
+    }
+
+    pub fn define(&mut self, name: &str, value: LiteralValue) {
+        self.members.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<LiteralValue> {
+        self.members.get(name).cloned()
+    }
+}
+
+impl fmt::Debug for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<namespace {}>", self.name)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::lox::error_manager::Error;
+use crate::lox::interpreter::Interpreter;
+use crate::tool::generate_ast::LiteralValue;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+pub type NativeFn = fn(&mut Interpreter, Vec<LiteralValue>) -> Result<LiteralValue, Error>;
+
+// A function implemented in Rust and exposed to scripts
+pub struct NativeFunction {
+    pub name: String,
+    pub arity: RangeInclusive<usize>,
+    pub function: NativeFn,
+    // When set, named arguments arrive as a map after the positional ones
+    pub named_arguments: bool,
+}
+
+impl NativeFunction {
+    pub fn new(name: &str, arity: RangeInclusive<usize>, function: NativeFn) -> Self {
+        NativeFunction { name: name.to_string(), arity, function, named_arguments: false }
+    }
+
+    pub fn with_named_arguments(mut self) -> Self {
+        self.named_arguments = true;
+        self
+    }
+
+    pub fn arity_description(&self) -> String {
+        if self.arity.start() == self.arity.end() {
+            self.arity.start().to_string()
+        } else if *self.arity.end() == usize::MAX {
+            format!("at least {}", self.arity.start())
+        } else {
+            format!("{} to {}", self.arity.start(), self.arity.end())
+        }
+    }
+}
+
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<native fn {}>", self.name)
+    }
+}
+
+// A native method looked up on a built-in value, e.g. `s.union`.
+// The receiver is passed to the method as its first argument.
+#[derive(Debug, PartialEq)]
+pub struct BoundMethod {
+    pub receiver: LiteralValue,
+    pub method: Rc<NativeFunction>,
+}
+
+impl BoundMethod {
+    pub fn new(receiver: LiteralValue, method: Rc<NativeFunction>) -> Self {
+        BoundMethod { receiver, method }
+    }
+}
@@ -1,9 +1,11 @@
-use crate::tool::generate_ast::{Ast, Expr, LiteralValue, Stmt};
+use crate::tool::generate_ast::{Expr, LiteralValue, Stmt};
 use crate::lox::token::{Token, TokenType};
-use crate::lox::error_manager::{self, ErrorManager};
+use crate::lox::error_manager::ErrorManager;
 use std::rc::Rc;
 use std::cell::RefCell;
 
+// Only read through Debug for now, every caller recovers and keeps parsing
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct ParseError {
     token: Token,
@@ -27,11 +29,49 @@ pub struct Parser
 
 impl Parser {
     pub fn new(tokens: Vec<Token>, error_manager: Rc<RefCell<ErrorManager>>) -> Self {
-        Parser {tokens, current:0, error_manager}
+        Parser {tokens: Self::attach_doc_comments(tokens), current:0, error_manager}
+    }
+
+    // Merges consecutive doc comments into one token and drops the ones that
+    // don't document a declaration, so the grammar only ever sees a doc
+    // comment right before `var`, `const`, `fun`, `record` or `enum`.
+    fn attach_doc_comments(tokens: Vec<Token>) -> Vec<Token> {
+        let mut result: Vec<Token> = Vec::with_capacity(tokens.len());
+        let mut pending: Option<Token> = None;
+        for token in tokens {
+            if token.token_type == TokenType::DocComment {
+                let text = token.literal.clone().unwrap_or_default();
+                match &mut pending {
+                    Some(doc) => {
+                        let merged = format!("{}\n{}", doc.literal.take().unwrap_or_default(), text);
+                        doc.literal = Some(merged);
+                    },
+                    None => pending = Some(token),
+                }
+                continue;
+            }
+            if let Some(doc) = pending.take() {
+                if matches!(token.token_type, TokenType::Var | TokenType::Const | TokenType::Fun | TokenType::Record | TokenType::Enum) {
+                    result.push(doc);
+                }
+            }
+            result.push(token);
+        }
+        result
+    }
+
+    fn with_doc(statement: Stmt, text: String) -> Stmt {
+        let mut statement = statement;
+        match &mut statement {
+            Stmt::Var { doc, .. } | Stmt::Const { doc, .. } | Stmt::Function { doc, .. }
+            | Stmt::Record { doc, .. } | Stmt::Enum { doc, .. } => *doc = Some(text),
+            _ => {},
+        }
+        statement
     }
 
     fn error(& mut self, token: &Token, message: &str)-> Result<(), ParseError> {
-        self.error_manager.borrow_mut().report(token.line, message, Some(&token.lexeme));
+        self.error_manager.borrow_mut().report_at(token.line, Some(token.column), message, Some(&token.lexeme));
         Err(ParseError::new(token.clone(), message))
     }
 
@@ -69,6 +109,8 @@ impl Parser {
         return false;
     }
 
+    // Not called yet, see the note at the end of `declaration`
+    #[allow(dead_code)]
     fn synchronize(&mut self) {
         self.advance_token();
         while !self.is_at_end() {
@@ -76,8 +118,8 @@ impl Parser {
                 return;
             }
             match self.peek_token().token_type {
-                TokenType::Class | TokenType::Fun | TokenType::Var | TokenType::For |
-                TokenType::If | TokenType::While | TokenType::Print | TokenType::Return => {
+                TokenType::Class | TokenType::Record | TokenType::Enum | TokenType::Fun | TokenType::Var | TokenType::Const | TokenType::For |
+                TokenType::If | TokenType::While | TokenType::Do | TokenType::Print | TokenType::Return => {
                     return;
                 }
                 _ => {}
@@ -90,7 +132,7 @@ impl Parser {
             self.advance_token();
         } else {
             let peeked_token = self.peek_token().clone();
-            self.error(
+            let _ = self.error(
                 &peeked_token, 
                 message);
         }
@@ -120,10 +162,40 @@ impl Parser {
         }
         if self.match_token_type(TokenType::LeftParen) {
         //    println!("Left parenthesis found: {:?}", self.previous().lexeme);
+            // `()` is the empty tuple
+            if self.match_token_type(TokenType::RightParen) {
+                return Expr::Tuple { elements: Vec::new() };
+            }
             let expr = self.expression();
+            // A comma turns the parentheses into a tuple: `(a, b)`, and `(a,)` for a single element
+            if self.match_token_type(TokenType::Comma) {
+                let mut elements = vec![expr];
+                while !self.check_token_type(TokenType::RightParen) && !self.is_at_end() {
+                    elements.push(self.expression());
+                    if !self.match_token_type(TokenType::Comma) {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RightParen, "Expect ')' after tuple elements.");
+                return Expr::Tuple { elements };
+            }
             self.consume(TokenType::RightParen, "Expect ')' after expression.");
             return Expr::Grouping { expression: Box::new(expr) };
         }
+        // `{key: value, ...}` in expression position is a map literal
+        if self.match_token_type(TokenType::LeftBrace) {
+            let mut entries = Vec::new();
+            while !self.check_token_type(TokenType::RightBrace) && !self.is_at_end() {
+                let key = self.expression();
+                self.consume(TokenType::Colon, "Expect ':' after map key.");
+                entries.push((key, self.expression()));
+                if !self.match_token_type(TokenType::Comma) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RightBrace, "Expect '}' after map entries.");
+            return Expr::Map { entries };
+        }
         if self.match_token_type(TokenType::Identifier) {
         //    println!("Identifier has been well identified: {}", self.previous().lexeme);
             return Expr::Variable { name: self.previous().lexeme.clone() };
@@ -131,21 +203,117 @@ impl Parser {
         
         }
 
-        panic!("Unexpected token: {}", self.peek_token().lexeme);
+        // Skip the offending token so that parsing always makes progress
+        let token = self.peek_token().clone();
+        let _ = self.error(&token, "Expect expression.");
+        self.advance_token();
+        Expr::Literal { value: LiteralValue::Nil }
     }
 
     fn unary(&mut self) -> Expr {
         // println!("Unary {:?}, {}", self.peek_token().token_type, self.peek_token().lexeme);
         if self.match_token_type(TokenType::Minus) || self.match_token_type(TokenType::Bang) {
             let operator = self.previous().lexeme.clone();
+            let line = self.previous().line;
             let right = self.unary();
             return Expr::Unary {
                 operator,
                 right: Box::new(right),
+                line,
             };
         }
 
-        return self.primary();
+        return self.call();
+    }
+
+    fn call(&mut self) -> Expr {
+        let mut expr = self.primary();
+        loop {
+            if self.match_token_type(TokenType::LeftParen) {
+                expr = self.finish_call(expr);
+            } else if self.match_token_type(TokenType::Dot) {
+                let name_token = self.peek_token().clone();
+                self.consume(TokenType::Identifier, "Expect property name after '.'.");
+                expr = Expr::Get { object: Box::new(expr), name: name_token.lexeme };
+            } else if self.match_token_type(TokenType::LeftBracket) {
+                expr = self.finish_index(expr);
+            } else if self.match_token_type(TokenType::With) {
+                expr = self.finish_with(expr);
+            } else {
+                break;
+            }
+        }
+        return expr;
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Expr {
+        let line = self.previous().line;
+        let mut arguments = Vec::new();
+        let mut named_arguments = Vec::new();
+        while !self.check_token_type(TokenType::RightParen) && !self.is_at_end() {
+            // `name: value` passes an argument by name
+            if self.check_token_type(TokenType::Identifier) && self.tokens.get(self.current + 1).is_some_and(|t| t.token_type == TokenType::Colon) {
+                let name = self.advance_token().lexeme.clone();
+                self.advance_token();
+                named_arguments.push((name, self.expression()));
+            } else {
+                let argument = self.expression();
+                if !named_arguments.is_empty() {
+                    let previous = self.previous().clone();
+                    let _ = self.error(&previous, "Positional arguments must come before named arguments.");
+                }
+                arguments.push(argument);
+            }
+            if !self.match_token_type(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after arguments.");
+        return Expr::Call { callee: Box::new(callee), arguments, named_arguments, line };
+    }
+
+    // s[i] or s[start:end:step] where each part of the slice is optional
+    fn finish_index(&mut self, object: Expr) -> Expr {
+        let line = self.previous().line;
+        let start = if self.check_token_type(TokenType::Colon) {
+            None
+        } else {
+            Some(Box::new(self.expression()))
+        };
+        if !self.match_token_type(TokenType::Colon) {
+            self.consume(TokenType::RightBracket, "Expect ']' after index.");
+            let index = start.expect("an index is parsed whenever ':' is absent");
+            return Expr::Index { object: Box::new(object), index, line };
+        }
+        let end = if self.check_token_type(TokenType::Colon) || self.check_token_type(TokenType::RightBracket) {
+            None
+        } else {
+            Some(Box::new(self.expression()))
+        };
+        let step = if self.match_token_type(TokenType::Colon) && !self.check_token_type(TokenType::RightBracket) {
+            Some(Box::new(self.expression()))
+        } else {
+            None
+        };
+        self.consume(TokenType::RightBracket, "Expect ']' after slice.");
+        return Expr::Slice { object: Box::new(object), start, end, step, line };
+    }
+
+    // p with { x: 3, y: 4 }
+    fn finish_with(&mut self, object: Expr) -> Expr {
+        self.consume(TokenType::LeftBrace, "Expect '{' after 'with'.");
+        let mut updates = Vec::new();
+        while !self.check_token_type(TokenType::RightBrace) && !self.is_at_end() {
+            let name = self.peek_token().lexeme.clone();
+            self.consume(TokenType::Identifier, "Expect field name in 'with' expression.");
+            self.consume(TokenType::Colon, "Expect ':' after field name.");
+            updates.push((name, self.expression()));
+            if !self.match_token_type(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBrace, "Expect '}' after 'with' fields.");
+        return Expr::With { object: Box::new(object), updates };
     }
 
     fn factor(&mut self) -> Expr {
@@ -154,11 +322,13 @@ impl Parser {
 
         while self.match_token_type(TokenType::Star) || self.match_token_type(TokenType::Slash) {
             let operator = self.previous().lexeme.clone();
+            let line = self.previous().line;
             let right = self.unary();
             expr = Expr::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                line,
             };
         }
         return expr;
@@ -170,11 +340,13 @@ impl Parser {
 
         while self.match_token_type(TokenType::Plus) || self.match_token_type(TokenType::Minus) {
             let operator = self.previous().lexeme.clone();
+            let line = self.previous().line;
             let right = self.factor();
             expr = Expr::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                line,
             };
         }
         return expr;
@@ -185,15 +357,26 @@ impl Parser {
         let mut expr = self.term();
         // println!("finished calling term, current token: {:?}", self.peek_token());
         while self.match_token_type(TokenType::Greater) || self.match_token_type(TokenType::GreaterEqual) ||
-              self.match_token_type(TokenType::Less) || self.match_token_type(TokenType::LessEqual) {
+              self.match_token_type(TokenType::Less) || self.match_token_type(TokenType::LessEqual) ||
+              self.match_token_type(TokenType::In) {
         //    println!("looop starting inside comparison");
             let operator = self.previous().lexeme.clone();
+            let line = self.previous().line;
         //    println!("Comparison operator: {}", operator);
             let right = self.term();
             expr = Expr::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                line,
+            };
+        }
+        // Variant and record test: `shape is Shape.Circle`
+        while self.match_token_type(TokenType::Is) {
+            let kind = self.term();
+            expr = Expr::Is {
+                value: Box::new(expr),
+                kind: Box::new(kind),
             };
         }
         return expr;
@@ -205,11 +388,13 @@ impl Parser {
         let mut expr = self.comparison();
         while self.match_token_type(TokenType::EqualEqual) || self.match_token_type(TokenType::BangEqual) {
             let operator_lexeme = self.previous().lexeme.clone();
+            let line = self.previous().line;
             let right = self.comparison();
             expr = Expr::Binary {
                 left: Box::new(expr),
                 operator: operator_lexeme,
                 right: Box::new(right),
+                line,
             };
         }
         return expr;
@@ -217,19 +402,46 @@ impl Parser {
 
     fn expression(&mut self) -> Expr {
         // IF ASSIGNMENT
-        if self.peek_token().token_type == TokenType::Identifier && self.tokens.get(self.current + 1).map_or(false, |t| t.token_type == TokenType::Equal) {
+        if self.peek_token().token_type == TokenType::Identifier && self.tokens.get(self.current + 1).is_some_and(|t| t.token_type == TokenType::Equal) {
             //println!("we are inside assignment");
             self.advance_token(); // Move past the identifier token
             let name = self.previous().lexeme.clone();
+            let line = self.previous().line;
             self.consume(TokenType::Equal, "Expect '=' after variable name.");
             let value = self.expression();
             return Expr::Assignment {
                 name,
                 value: Box::new(value),
+                line,
             };
         }
         // if not in an assignment, check for logical OR
-        self.logical_or()
+        let expr = self.logical_or();
+        // A tuple of variables on the left of '=' destructures the value: (a, b) = (b, a)
+        if self.match_token_type(TokenType::Equal) {
+            let equals = self.previous().clone();
+            let value = self.expression();
+            if let Some(names) = Self::tuple_target_names(&expr) {
+                return Expr::TupleAssignment { names, value: Box::new(value), line: equals.line };
+            }
+            if let Expr::Get { name, .. } = &expr {
+                let _ = self.error(&equals, &format!("Cannot assign to field '{}': record fields are read-only.", name));
+                return expr;
+            }
+            let _ = self.error(&equals, "Invalid assignment target.");
+        }
+        expr
+    }
+
+    // Names of a tuple expression made only of variables, the only valid destructuring target
+    fn tuple_target_names(expr: &Expr) -> Option<Vec<String>> {
+        if let Expr::Tuple { elements } = expr {
+            return elements.iter().map(|element| match element {
+                Expr::Variable { name } => Some(name.clone()),
+                _ => None,
+            }).collect();
+        }
+        None
     }
 
     fn print_statement(&mut self) -> Stmt {
@@ -238,6 +450,33 @@ impl Parser {
         return Stmt::Print(value);
     }
 
+    fn assert_statement(&mut self) -> Stmt {
+        let line = self.previous().line;
+        let condition = self.expression();
+        let message = if self.match_token_type(TokenType::Comma) {
+            Some(self.expression())
+        } else {
+            None
+        };
+        self.consume(TokenType::Semicolon, "Expect ';' after assertion.");
+        return Stmt::Assert { condition, message, line };
+    }
+
+    fn test_declaration(&mut self) -> Stmt {
+        self.advance_token();
+        let name = self.previous().literal.clone().unwrap_or_default();
+        if !self.match_token_type(TokenType::LeftBrace) {
+            let message = "Expect '{' before test body.";
+            let peeked_token = self.peek_token().clone();
+            let _ = self.error(&peeked_token, message);
+            return Stmt::Error(message.to_string());
+        }
+        let Stmt::Block(body) = self.block_statement() else {
+            unreachable!("block_statement always returns a block");
+        };
+        return Stmt::Test { name, body };
+    }
+
     fn block_statement(&mut self) -> Stmt {
         let mut statements = Vec::new();
         while !self.is_at_end() && !self.check_token_type(TokenType::RightBrace) {
@@ -282,7 +521,7 @@ impl Parser {
             else_branch,
         }
     }
-    fn while_statement(&mut self) -> Stmt {
+    fn while_statement(&mut self, label: Option<String>) -> Stmt {
         self.consume(TokenType::LeftParen, "Expect '(' after 'while'.");
         let condition = self.expression();
         self.consume(TokenType::RightParen, "Expect ')' after while condition.");
@@ -299,11 +538,73 @@ impl Parser {
         return Stmt::While {
             condition,
             body,
+            increment: None,
+            label,
         }; 
     }
 
+    fn do_while_statement(&mut self, label: Option<String>) -> Stmt {
+        self.consume(TokenType::LeftBrace, "Expect '{' after 'do'.");
+        let mut statements = Vec::new();
+        while !self.check_token_type(TokenType::RightBrace) && !self.is_at_end() {
+            statements.push(self.statement());
+        }
+        self.consume(TokenType::RightBrace, "Expect '}' after do body.");
+        self.consume(TokenType::While, "Expect 'while' after do body.");
+        self.consume(TokenType::LeftParen, "Expect '(' after 'while'.");
+        let condition = self.expression();
+        self.consume(TokenType::RightParen, "Expect ')' after while condition.");
+        self.consume(TokenType::Semicolon, "Expect ';' after do-while statement.");
 
-    fn for_statement(&mut self) -> Stmt {
+        return Stmt::DoWhile {
+            condition,
+            body: Box::new(Stmt::Sequence(statements)),
+            label,
+        };
+    }
+
+    fn labeled_statement(&mut self) -> Stmt {
+        let label = self.previous().lexeme.clone();
+        self.consume(TokenType::Colon, "Expect ':' after label.");
+        if self.match_token_type(TokenType::While) {
+            return self.while_statement(Some(label));
+        }
+        if self.match_token_type(TokenType::For) {
+            return self.for_statement(Some(label));
+        }
+        if self.match_token_type(TokenType::Do) {
+            return self.do_while_statement(Some(label));
+        }
+        let message = format!("Label '{}' must be followed by a loop.", label);
+        let peeked_token = self.peek_token().clone();
+        let _ = self.error(&peeked_token, &message);
+        return Stmt::Error(message);
+    }
+
+    fn break_statement(&mut self) -> Stmt {
+        let line = self.previous().line;
+        let label = if self.match_token_type(TokenType::Identifier) {
+            Some(self.previous().lexeme.clone())
+        } else {
+            None
+        };
+        self.consume(TokenType::Semicolon, "Expect ';' after 'break'.");
+        return Stmt::Break { label, line };
+    }
+
+    fn continue_statement(&mut self) -> Stmt {
+        let line = self.previous().line;
+        let label = if self.match_token_type(TokenType::Identifier) {
+            Some(self.previous().lexeme.clone())
+        } else {
+            None
+        };
+        self.consume(TokenType::Semicolon, "Expect ';' after 'continue'.");
+        return Stmt::Continue { label, line };
+    }
+
+
+    fn for_statement(&mut self, label: Option<String>) -> Stmt {
         self.consume(TokenType::LeftParen, "Expect '(' after 'for'.");
         // Initializer 
         let initializer = if self.match_token_type(TokenType::Semicolon) {
@@ -328,13 +629,13 @@ impl Parser {
         };
         self.consume(TokenType::RightParen, "Expect ')' after for clauses.");
         // Body 
-        let mut body = self.statement();
-        if let Some(increment_expr) = increment {
-            body = Stmt::Block(vec![body, Stmt::Expression(increment_expr)]);
-        }
-        body = Stmt::While {
+        let body = self.statement();
+        // The increment stays outside the body so that `continue` still runs it
+        let mut body = Stmt::While {
             condition,
             body: Box::new(body),
+            increment,
+            label,
         };
         if let Some(init_stmt) = initializer {
             body = Stmt::Block(vec![init_stmt, body]);
@@ -344,14 +645,14 @@ impl Parser {
     }
 
     fn return_statement(&mut self) -> Stmt {
-        let keyword = self.previous().lexeme.clone();
+        let line = self.previous().line;
         let value = if !self.is_at_end() && !self.check_token_type(TokenType::Semicolon) {
             Some(self.expression())
         } else {
             None
         };
         self.consume(TokenType::Semicolon, "Expect ';' after return value.");
-        return Stmt::Return { keyword, value };
+        return Stmt::Return { value, line };
 
     }
     
@@ -365,9 +666,21 @@ impl Parser {
     }
 
     fn statement(&mut self) -> Stmt {
+        if self.match_token_type(TokenType::DocComment) {
+            let text = self.previous().literal.clone().unwrap_or_default();
+            let statement = self.statement();
+            return Self::with_doc(statement, text);
+        }
         if self.match_token_type(TokenType::Print) {
             return self.print_statement();
         }
+        if self.match_token_type(TokenType::Assert) {
+            return self.assert_statement();
+        }
+        if self.match_token_type(TokenType::Defer) {
+            let line = self.previous().line;
+            return Stmt::Defer { statement: Box::new(self.statement()), line };
+        }
         if self.match_token_type(TokenType::LeftBrace) {
             return self.block_statement();
         }
@@ -375,10 +688,24 @@ impl Parser {
             return self.if_statement();
         }
         if self.match_token_type(TokenType::While) {
-            return self.while_statement();
+            return self.while_statement(None);
         }
         if self.match_token_type(TokenType::For) {
-            return self.for_statement();
+            return self.for_statement(None);
+        }
+        if self.match_token_type(TokenType::Do) {
+            return self.do_while_statement(None);
+        }
+        if self.match_token_type(TokenType::Break) {
+            return self.break_statement();
+        }
+        if self.match_token_type(TokenType::Continue) {
+            return self.continue_statement();
+        }
+        // A label is an identifier directly followed by ':'
+        if self.check_token_type(TokenType::Identifier) && self.tokens.get(self.current + 1).is_some_and(|t| t.token_type == TokenType::Colon) {
+            self.advance_token();
+            return self.labeled_statement();
         }
         if self.match_token_type(TokenType::Return) {
             return self.return_statement();
@@ -386,30 +713,208 @@ impl Parser {
         if self.match_token_type(TokenType::Var) {
             return self.var_declaration();
         }
+        if self.match_token_type(TokenType::Const) {
+            return self.const_declaration();
+        }
+        if self.match_token_type(TokenType::Record) {
+            return self.record_declaration();
+        }
+        if self.match_token_type(TokenType::Fun) {
+            return self.function_declaration();
+        }
+        if self.match_token_type(TokenType::Enum) {
+            return self.enum_declaration();
+        }
         // Other statement to be added here
         return self.expression_statement();
 
     }
 
     fn var_declaration(&mut self) -> Stmt {
+        if self.match_token_type(TokenType::LeftParen) {
+            return self.destructuring_declaration();
+        }
         //println!("We are inside var_declaration");
         let name = self.peek_token().lexeme.clone(); // Assuming the variable name is the previous token
+        let line = self.peek_token().line;
         // println!("Variable name: {}", name);
         self.advance_token(); // Move past the variable name token
+        let type_annotation = self.type_annotation();
         let initializer = if self.match_token_type(TokenType::Equal) {
             Some(self.expression())
         } else {
             None
         };
         self.consume(TokenType::Semicolon, "Expect ';' after variable declaration.");
-        return Stmt::Var { name, initializer }
+        return Stmt::Var { name, type_annotation, initializer, line, doc: None }
+    }
+
+    // Optional `: Type` after a name. Annotations are only read by the type checker.
+    fn type_annotation(&mut self) -> Option<String> {
+        if !self.match_token_type(TokenType::Colon) {
+            return None;
+        }
+        if self.match_token_type(TokenType::Identifier) || self.match_token_type(TokenType::Nil) {
+            return Some(self.previous().lexeme.clone());
+        }
+        let peeked_token = self.peek_token().clone();
+        let _ = self.error(&peeked_token, "Expect type name after ':'.");
+        None
+    }
+
+    fn function_declaration(&mut self) -> Stmt {
+        let name_token = self.peek_token().clone();
+        self.consume(TokenType::Identifier, "Expect function name.");
+        self.consume(TokenType::LeftParen, "Expect '(' after function name.");
+        let mut parameters: Vec<(String, Option<String>)> = Vec::new();
+        while !self.check_token_type(TokenType::RightParen) && !self.is_at_end() {
+            let parameter_token = self.peek_token().clone();
+            self.consume(TokenType::Identifier, "Expect parameter name.");
+            if parameters.iter().any(|(name, _)| *name == parameter_token.lexeme) {
+                let _ = self.error(&parameter_token, &format!("Duplicate parameter '{}'.", parameter_token.lexeme));
+            }
+            let parameter_type = self.type_annotation();
+            parameters.push((parameter_token.lexeme, parameter_type));
+            if !self.match_token_type(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after parameters.");
+        let return_type = self.type_annotation();
+        self.consume(TokenType::LeftBrace, "Expect '{' before function body.");
+        let mut body = Vec::new();
+        while !self.check_token_type(TokenType::RightBrace) && !self.is_at_end() {
+            body.push(self.statement());
+        }
+        self.consume(TokenType::RightBrace, "Expect '}' after function body.");
+        return Stmt::Function { name: name_token.lexeme, parameters, return_type, body, line: name_token.line, doc: None }
+    }
+
+    fn destructuring_declaration(&mut self) -> Stmt {
+        let line = self.previous().line;
+        let mut names = Vec::new();
+        while !self.check_token_type(TokenType::RightParen) && !self.is_at_end() {
+            names.push(self.peek_token().lexeme.clone());
+            self.consume(TokenType::Identifier, "Expect variable name in destructuring declaration.");
+            if !self.match_token_type(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after destructured names.");
+        if !self.match_token_type(TokenType::Equal) {
+            let message = "Destructuring declaration must be initialized.";
+            let peeked_token = self.peek_token().clone();
+            let _ = self.error(&peeked_token, message);
+            self.match_token_type(TokenType::Semicolon);
+            return Stmt::Error(message.to_string());
+        }
+        let initializer = self.expression();
+        self.consume(TokenType::Semicolon, "Expect ';' after variable declaration.");
+        return Stmt::Destructure { names, initializer, line }
+    }
+
+    fn record_declaration(&mut self) -> Stmt {
+        let name_token = self.peek_token().clone();
+        self.consume(TokenType::Identifier, "Expect record name.");
+        self.consume(TokenType::LeftParen, "Expect '(' after record name.");
+        let mut fields: Vec<String> = Vec::new();
+        while !self.check_token_type(TokenType::RightParen) && !self.is_at_end() {
+            let field_token = self.peek_token().clone();
+            self.consume(TokenType::Identifier, "Expect field name.");
+            if fields.contains(&field_token.lexeme) {
+                let _ = self.error(&field_token, &format!("Duplicate field '{}' in record '{}'.", field_token.lexeme, name_token.lexeme));
+            }
+            fields.push(field_token.lexeme);
+            if !self.match_token_type(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after record fields.");
+        self.consume(TokenType::Semicolon, "Expect ';' after record declaration.");
+        return Stmt::Record { name: name_token.lexeme, fields, line: name_token.line, doc: None }
+    }
+
+    fn enum_declaration(&mut self) -> Stmt {
+        let name_token = self.peek_token().clone();
+        self.consume(TokenType::Identifier, "Expect enum name.");
+        self.consume(TokenType::LeftBrace, "Expect '{' after enum name.");
+        let mut variants: Vec<(String, Vec<String>)> = Vec::new();
+        while !self.check_token_type(TokenType::RightBrace) && !self.is_at_end() {
+            let variant_token = self.peek_token().clone();
+            self.consume(TokenType::Identifier, "Expect variant name.");
+            if variants.iter().any(|(name, _)| *name == variant_token.lexeme) {
+                let _ = self.error(&variant_token, &format!("Duplicate variant '{}' in enum '{}'.", variant_token.lexeme, name_token.lexeme));
+            }
+            // Optional payload: Rect(w, h)
+            let mut fields: Vec<String> = Vec::new();
+            if self.match_token_type(TokenType::LeftParen) {
+                while !self.check_token_type(TokenType::RightParen) && !self.is_at_end() {
+                    let field_token = self.peek_token().clone();
+                    self.consume(TokenType::Identifier, "Expect field name.");
+                    if fields.contains(&field_token.lexeme) {
+                        let _ = self.error(&field_token, &format!("Duplicate field '{}' in variant '{}'.", field_token.lexeme, variant_token.lexeme));
+                    }
+                    fields.push(field_token.lexeme);
+                    if !self.match_token_type(TokenType::Comma) {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RightParen, "Expect ')' after variant fields.");
+            }
+            variants.push((variant_token.lexeme, fields));
+            if !self.match_token_type(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBrace, "Expect '}' after enum variants.");
+        return Stmt::Enum { name: name_token.lexeme, variants, line: name_token.line, doc: None }
+    }
+
+    fn const_declaration(&mut self) -> Stmt {
+        let name_token = self.peek_token().clone();
+        self.consume(TokenType::Identifier, "Expect constant name.");
+        let type_annotation = self.type_annotation();
+        // A constant can never be assigned later, so the initializer is mandatory
+        if !self.match_token_type(TokenType::Equal) {
+            let message = format!("Constant '{}' must be initialized.", name_token.lexeme);
+            let peeked_token = self.peek_token().clone();
+            let _ = self.error(&peeked_token, &message);
+            self.match_token_type(TokenType::Semicolon);
+            return Stmt::Error(message);
+        }
+        let initializer = self.expression();
+        self.consume(TokenType::Semicolon, "Expect ';' after constant declaration.");
+        return Stmt::Const { name: name_token.lexeme, type_annotation, initializer, line: name_token.line, doc: None }
     }
 
     fn declaration(&mut self) -> Stmt {
+        if self.match_token_type(TokenType::DocComment) {
+            let text = self.previous().literal.clone().unwrap_or_default();
+            let declaration = self.declaration();
+            return Self::with_doc(declaration, text);
+        }
         // println!("Declaration {:?}, {}", self.peek_token().token_type, self.peek_token().lexeme);
         if self.match_token_type(TokenType::Var) {
             return self.var_declaration()
         }
+        if self.match_token_type(TokenType::Const) {
+            return self.const_declaration()
+        }
+        if self.match_token_type(TokenType::Record) {
+            return self.record_declaration()
+        }
+        if self.match_token_type(TokenType::Fun) {
+            return self.function_declaration()
+        }
+        if self.match_token_type(TokenType::Enum) {
+            return self.enum_declaration()
+        }
+        // `test "name" { ... }`; `test` is only special here, it stays a normal identifier elsewhere
+        if self.check_token_type(TokenType::Identifier) && self.peek_token().lexeme == "test"
+            && self.tokens.get(self.current + 1).is_some_and(|t| t.token_type == TokenType::StringLiteral) {
+            self.advance_token();
+            return self.test_declaration();
+        }
         return self.statement();
         //To add : catch parse errors in which case synchronize
         }
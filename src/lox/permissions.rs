@@ -0,0 +1,135 @@
+use std::path::{Component, Path, PathBuf};
+
+// Capabilities granted to a script from the command line.
+// Everything is denied by default.
+#[derive(Debug, Clone, Default)]
+pub struct Permissions {
+    // Canonical directories the script may read and write under (--allow-fs=<dir>)
+    fs_roots: Vec<PathBuf>,
+    // Whether `run` may spawn processes (--allow-run)
+    pub allow_run: bool,
+}
+
+impl Permissions {
+    pub fn new() -> Self {
+        Permissions::default()
+    }
+
+    pub fn allow_fs(&mut self, root: &str) -> Result<(), String> {
+        let canonical = Path::new(root).canonicalize()
+            .map_err(|e| format!("Cannot allow file system access to '{}': {}", root, e))?;
+        self.fs_roots.push(canonical);
+        Ok(())
+    }
+
+    // Resolves `path` and checks that it stays inside an allowed root.
+    // Symlinks are followed for the part of the path that already exists,
+    // so a link can't be used to escape the roots.
+    pub fn check_fs(&self, path: &str) -> Result<PathBuf, String> {
+        let resolved = resolve_existing_prefix(&self.absolute(path)?);
+        self.check_inside(path, resolved)
+    }
+
+    // Like `check_fs` for operations on the directory entry itself, such as
+    // removing it: only the parent is resolved, so a symlink names the link
+    // rather than its target. An allowed root can't be such an entry.
+    pub fn check_fs_entry(&self, path: &str) -> Result<PathBuf, String> {
+        let absolute = self.absolute(path)?;
+        let (Some(parent), Some(name)) = (absolute.parent(), absolute.file_name()) else {
+            return Err(format!("Access to '{}' is outside of the allowed directories.", path));
+        };
+        let entry = resolve_existing_prefix(parent).join(name);
+        if self.fs_roots.contains(&entry) {
+            return Err(format!("'{}' is an allowed directory and can't be changed itself.", path));
+        }
+        self.check_inside(path, entry)
+    }
+
+    fn absolute(&self, path: &str) -> Result<PathBuf, String> {
+        if self.fs_roots.is_empty() {
+            return Err("File system access is disabled, run with --allow-fs=<dir> to enable it.".to_string());
+        }
+        std::env::current_dir()
+            .map(|cwd| normalize_path(&cwd.join(path)))
+            .map_err(|e| format!("Cannot resolve '{}': {}", path, e))
+    }
+
+    fn check_inside(&self, path: &str, resolved: PathBuf) -> Result<PathBuf, String> {
+        if self.fs_roots.iter().any(|root| resolved.starts_with(root)) {
+            Ok(resolved)
+        } else {
+            Err(format!("Access to '{}' is outside of the allowed directories.", path))
+        }
+    }
+}
+
+// Removes `.` and `..` components without touching the file system
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            // `..` cancels a preceding name; above the root it stays at the root,
+            // and in a relative path with nothing left to cancel it is kept
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                },
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {},
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        normalized.push(".");
+    }
+    normalized
+}
+
+// Canonicalizes the longest existing ancestor of an absolute, normalized path
+fn resolve_existing_prefix(path: &Path) -> PathBuf {
+    let mut existing = path.to_path_buf();
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing.iter().rev().fold(canonical, |resolved, part| resolved.join(part));
+        }
+        match (existing.file_name().map(|name| name.to_os_string()), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                missing.push(name);
+                existing = parent.to_path_buf();
+            },
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(path: &str) -> PathBuf {
+        normalize_path(Path::new(path))
+    }
+
+    #[test]
+    fn parent_dirs_cancel_names() {
+        assert_eq!(normalize("a/b/../c"), PathBuf::from("a/c"));
+        assert_eq!(normalize("a/./b/.."), PathBuf::from("a"));
+        assert_eq!(normalize("a/.."), PathBuf::from("."));
+    }
+
+    #[test]
+    fn leading_parent_dirs_are_kept() {
+        assert_eq!(normalize(".."), PathBuf::from(".."));
+        assert_eq!(normalize("../.."), PathBuf::from("../.."));
+        assert_eq!(normalize("a/../../b"), PathBuf::from("../b"));
+    }
+
+    #[test]
+    fn parent_dirs_stop_at_the_root() {
+        assert_eq!(normalize("/.."), PathBuf::from("/"));
+        assert_eq!(normalize("/a/../../b"), PathBuf::from("/b"));
+    }
+}
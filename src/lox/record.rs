@@ -0,0 +1,60 @@
+use crate::lox::display::fmt_nested;
+use crate::tool::generate_ast::LiteralValue;
+use std::fmt;
+use std::rc::Rc;
+
+// A `record Point(x, y);` declaration. The type itself is the constructor.
+#[derive(Debug)]
+pub struct RecordType {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+// Each declaration is its own type, even when another one looks the same
+impl PartialEq for RecordType {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl RecordType {
+    pub fn new(name: String, fields: Vec<String>) -> Self {
+        RecordType { name, fields }
+    }
+
+    pub fn field_index(&self, field: &str) -> Option<usize> {
+        self.fields.iter().position(|name| name == field)
+    }
+}
+
+// A record value. Fields are read-only, `with` builds an updated copy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordInstance {
+    pub record_type: Rc<RecordType>,
+    pub values: Vec<LiteralValue>,
+}
+
+impl RecordInstance {
+    pub fn new(record_type: Rc<RecordType>, values: Vec<LiteralValue>) -> Self {
+        RecordInstance { record_type, values }
+    }
+
+    pub fn get(&self, field: &str) -> Option<LiteralValue> {
+        self.record_type.field_index(field).map(|index| self.values[index].clone())
+    }
+}
+
+// Prints as `Point(x: 1, y: 2)`
+impl fmt::Display for RecordInstance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.record_type.name)?;
+        for (index, (field, value)) in self.record_type.fields.iter().zip(&self.values).enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: ", field)?;
+            fmt_nested(f, value)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,284 @@
+use crate::lox::error_manager::ErrorManager;
+use crate::tool::generate_ast::{Expr, Stmt};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+// What the resolver knows about a name declared in a scope.
+#[derive(Debug, Clone)]
+struct Binding {
+    // Line of the `const` declaration, `None` for plain variables
+    constant_line: Option<usize>,
+}
+
+// Static pass run between parsing and interpretation.
+// It mirrors the scoping rules of the interpreter (a `Stmt::Block` opens a
+// new scope, a `Stmt::Sequence` does not) and reports every assignment to a
+// constant, and every `break`/`continue` outside of a matching loop, before
+// a single statement is executed.
+pub struct Resolver {
+    scopes: Vec<HashMap<String, Binding>>,
+    // Labels of the loops enclosing the current statement, innermost last
+    loops: Vec<Option<String>>,
+    // Number of function bodies enclosing the current statement
+    function_depth: usize,
+    // The function depth of each enclosing `defer`, innermost last
+    defers: Vec<usize>,
+    // Number of blocks and braced bodies that would collect a `defer` here
+    defer_frames: usize,
+    error_manager: Rc<RefCell<ErrorManager>>,
+}
+
+impl Resolver {
+    pub fn new(error_manager: Rc<RefCell<ErrorManager>>) -> Self {
+        Resolver {
+            scopes: vec![HashMap::new()],
+            loops: Vec::new(),
+            function_depth: 0,
+            defers: Vec::new(),
+            defer_frames: 0,
+            error_manager,
+        }
+    }
+
+    pub fn resolve(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.resolve_statement(statement);
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    // Redeclaring a variable creates a fresh binding, exactly like `Environment::define`,
+    // but a constant can't be redeclared in its own scope
+    fn declare(&mut self, name: &str, constant_line: Option<usize>, line: usize) {
+        let scope = self.scopes.last_mut().expect("Resolver always has a global scope");
+        if let Some(Binding { constant_line: Some(declared_line) }) = scope.get(name) {
+            let declared_line = *declared_line;
+            self.error_manager.borrow_mut().report(
+                line,
+                &format!("Cannot redeclare constant '{}' declared on line {}.", name, declared_line),
+                Some(name));
+            return;
+        }
+        scope.insert(name.to_string(), Binding { constant_line });
+    }
+
+    fn check_assignable(&mut self, name: &str, line: usize) {
+        if let Some(Binding { constant_line: Some(declared_line) }) = self.lookup(name) {
+            let declared_line = *declared_line;
+            self.error_manager.borrow_mut().report(
+                line,
+                &format!("Cannot assign to constant '{}' declared on line {}.", name, declared_line),
+                Some(name));
+        }
+    }
+
+    // `var (x, x) = ...` and `(x, x) = ...` would silently drop one of the values
+    fn check_distinct(&mut self, names: &[String], line: usize, context: &str) {
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                self.error_manager.borrow_mut().report(
+                    line,
+                    &format!("Name '{}' appears more than once in {}.", name, context),
+                    Some(name));
+            }
+        }
+    }
+
+    // Statements run with their own frame of deferred statements
+    fn resolve_deferring(&mut self, statements: &[Stmt]) {
+        self.defer_frames += 1;
+        self.resolve(statements);
+        self.defer_frames -= 1;
+    }
+
+    fn resolve_loop_body(&mut self, body: &Stmt, label: &Option<String>) {
+        self.loops.push(label.clone());
+        self.resolve_statement(body);
+        self.loops.pop();
+    }
+
+    fn resolve_jump(&mut self, keyword: &str, label: &Option<String>, line: usize) {
+        if self.loops.is_empty() {
+            self.error_manager.borrow_mut().report(
+                line,
+                &format!("Cannot use '{}' outside of a loop.", keyword),
+                Some(keyword));
+        } else if let Some(label) = label {
+            if !self.loops.iter().any(|enclosing| enclosing.as_ref() == Some(label)) {
+                self.error_manager.borrow_mut().report(
+                    line,
+                    &format!("Undefined loop label '{}' for '{}'.", label, keyword),
+                    Some(label));
+            }
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &Stmt) {
+        match statement {
+            Stmt::Expression(expr) | Stmt::Print(expr) => self.resolve_expression(expr),
+            Stmt::Var { name, initializer, line, .. } => {
+                if let Some(initializer) = initializer {
+                    self.resolve_expression(initializer);
+                }
+                self.declare(name, None, *line);
+            },
+            Stmt::Destructure { names, initializer, line } => {
+                self.resolve_expression(initializer);
+                self.check_distinct(names, *line, "a destructuring declaration");
+                for name in names {
+                    self.declare(name, None, *line);
+                }
+            },
+            // Record and enum names are bound to their types and can't be reassigned
+            Stmt::Record { name, line, .. } | Stmt::Enum { name, line, .. } => self.declare(name, Some(*line), *line),
+            Stmt::Const { name, initializer, line, .. } => {
+                self.resolve_expression(initializer);
+                self.declare(name, Some(*line), *line);
+            },
+            Stmt::Block(statements) | Stmt::Test { body: statements, .. } => {
+                self.begin_scope();
+                self.resolve_deferring(statements);
+                self.end_scope();
+            },
+            Stmt::Assert { condition, message, .. } => {
+                self.resolve_expression(condition);
+                if let Some(message) = message {
+                    self.resolve_expression(message);
+                }
+            },
+            Stmt::Defer { statement, line } => {
+                if self.defer_frames == 0 {
+                    self.error_manager.borrow_mut().report(*line, "Cannot use 'defer' outside of a block.", Some("defer"));
+                }
+                if let Stmt::Defer { line, .. } = statement.as_ref() {
+                    self.error_manager.borrow_mut().report(*line, "Cannot defer a 'defer' statement.", Some("defer"));
+                }
+                // Deferred code runs as the block exits, it can't jump out of it
+                let enclosing_loops = std::mem::take(&mut self.loops);
+                self.defers.push(self.function_depth);
+                self.resolve_statement(statement);
+                self.defers.pop();
+                self.loops = enclosing_loops;
+            },
+            Stmt::Sequence(statements) => self.resolve_deferring(statements),
+            Stmt::If { condition, then_branch, else_branch } => {
+                self.resolve_expression(condition);
+                self.resolve_statement(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.resolve_statement(else_branch);
+                }
+            },
+            Stmt::While { condition, body, increment, label } => {
+                self.resolve_expression(condition);
+                if let Some(increment) = increment {
+                    self.resolve_expression(increment);
+                }
+                self.resolve_loop_body(body, label);
+            },
+            Stmt::DoWhile { body, condition, label } => {
+                self.resolve_loop_body(body, label);
+                self.resolve_expression(condition);
+            },
+            Stmt::Break { label, line } => self.resolve_jump("break", label, *line),
+            Stmt::Continue { label, line } => self.resolve_jump("continue", label, *line),
+            Stmt::Function { name, parameters, body, line, .. } => {
+                self.declare(name, None, *line);
+                // Loops outside of the function can't be targeted from its body
+                let enclosing_loops = std::mem::take(&mut self.loops);
+                self.function_depth += 1;
+                self.begin_scope();
+                for (parameter, _) in parameters {
+                    self.declare(parameter, None, *line);
+                }
+                self.resolve_deferring(body);
+                self.end_scope();
+                self.function_depth -= 1;
+                self.loops = enclosing_loops;
+            },
+            Stmt::Return { value, line, .. } => {
+                if self.function_depth == 0 {
+                    self.error_manager.borrow_mut().report(*line, "Cannot return from top-level code.", Some("return"));
+                } else if self.defers.last() == Some(&self.function_depth) {
+                    self.error_manager.borrow_mut().report(*line, "Cannot return from a deferred statement.", Some("return"));
+                }
+                if let Some(value) = value {
+                    self.resolve_expression(value);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn resolve_expression(&mut self, expression: &Expr) {
+        match expression {
+            Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+                self.resolve_expression(left);
+                self.resolve_expression(right);
+            },
+            Expr::Grouping { expression } => self.resolve_expression(expression),
+            Expr::Unary { right, .. } => self.resolve_expression(right),
+            Expr::Assignment { name, value, line } => {
+                self.resolve_expression(value);
+                self.check_assignable(name, *line);
+            },
+            Expr::TupleAssignment { names, value, line } => {
+                self.resolve_expression(value);
+                self.check_distinct(names, *line, "a destructuring assignment");
+                for name in names {
+                    self.check_assignable(name, *line);
+                }
+            },
+            Expr::Call { callee, arguments, named_arguments, .. } => {
+                self.resolve_expression(callee);
+                for argument in arguments.iter().chain(named_arguments.iter().map(|(_, argument)| argument)) {
+                    self.resolve_expression(argument);
+                }
+            },
+            Expr::Get { object, .. } => self.resolve_expression(object),
+            Expr::Index { object, index, .. } => {
+                self.resolve_expression(object);
+                self.resolve_expression(index);
+            },
+            Expr::Slice { object, start, end, step, .. } => {
+                self.resolve_expression(object);
+                for part in [start, end, step].into_iter().flatten() {
+                    self.resolve_expression(part);
+                }
+            },
+            Expr::Is { value, kind, .. } => {
+                self.resolve_expression(value);
+                self.resolve_expression(kind);
+            },
+            Expr::With { object, updates, .. } => {
+                self.resolve_expression(object);
+                for (_, update) in updates {
+                    self.resolve_expression(update);
+                }
+            },
+            Expr::Tuple { elements } => {
+                for element in elements {
+                    self.resolve_expression(element);
+                }
+            },
+            Expr::Map { entries, .. } => {
+                for (key, value) in entries {
+                    self.resolve_expression(key);
+                    self.resolve_expression(value);
+                }
+            },
+            Expr::Literal { .. } | Expr::Variable { .. } => {},
+        }
+    }
+}
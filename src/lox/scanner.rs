@@ -10,21 +10,32 @@ use std::cell::RefCell;
 static KEYWORDS: Lazy<HashMap<&'static str, TokenType>> = Lazy::new(|| {
     let mut m = HashMap::new();
     m.insert("and", TokenType::And);
+    m.insert("assert", TokenType::Assert);
+    m.insert("break", TokenType::Break);
     m.insert("class", TokenType::Class);
+    m.insert("const", TokenType::Const);
+    m.insert("continue", TokenType::Continue);
+    m.insert("defer", TokenType::Defer);
+    m.insert("do", TokenType::Do);
     m.insert("else", TokenType::Else);
+    m.insert("enum", TokenType::Enum);
     m.insert("false", TokenType::False);
     m.insert("for", TokenType::For);
     m.insert("fun", TokenType::Fun);
     m.insert("if", TokenType::If);
+    m.insert("in", TokenType::In);
+    m.insert("is", TokenType::Is);
     m.insert("nil", TokenType::Nil);
     m.insert("or", TokenType::Or);
     m.insert("print", TokenType::Print);
+    m.insert("record", TokenType::Record);
     m.insert("return", TokenType::Return);
     m.insert("super", TokenType::Super);
     m.insert("this", TokenType::This);
     m.insert("true", TokenType::True);
     m.insert("var", TokenType::Var);
     m.insert("while", TokenType::While);
+    m.insert("with", TokenType::With);
     m.insert("&", TokenType::And);
     m.insert("||", TokenType::Or);
     m
@@ -39,6 +50,10 @@ pub struct Scanner
     start: usize,
     current: usize,
     line: usize,
+    // Byte offset where the current line begins
+    line_start: usize,
+    // Column of `start`, kept even when a string runs onto later lines
+    start_column: usize,
     error_manager: Rc<RefCell<ErrorManager>>, 
 }
 
@@ -51,6 +66,8 @@ impl Scanner {
             start: 0,
             current: 0,
             line: 1,
+            line_start: 0,
+            start_column: 1,
             error_manager,
         }
     }
@@ -60,17 +77,33 @@ impl Scanner {
         return self.current >= self.source.len();
     }
 
+    // `start` and `current` are byte offsets, so they always step over whole characters
     fn advance(&mut self) -> char
     {
-        let c = self.source.chars().nth(self.current).unwrap();
-        self.current += 1;
+        let c = self.source[self.current..].chars().next().unwrap();
+        self.current += c.len_utf8();
         return c;
     }
 
+    // Column of the byte offset `at`, which must be on the current line
+    fn column(&self, at: usize) -> usize {
+        self.source[self.line_start..at].chars().count() + 1
+    }
+
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
+    // Errors point at the start of the token being scanned
+    fn error(&mut self, message: &str) {
+        self.error_manager.borrow_mut().report_at(self.line, Some(self.start_column), message, None);
+    }
+
     fn add_token(&mut self, token_type:TokenType, literal: Option<String>) {
         let text = &self.source[self.start..self.current];
         //println!("Adding token: {:?} with text: {}", token_type, text);
-        self.tokens.push(Token::new(token_type, text.to_string(), literal, self.line));
+        self.tokens.push(Token::new(token_type, text.to_string(), literal, self.line, self.start_column));
 
     }
      
@@ -78,10 +111,10 @@ impl Scanner {
         if self.is_at_end() {
             return false;
         }
-        if self.source.chars().nth(self.current).unwrap() != expected {
+        if self.peek() != expected {
             return false;
         }
-        self.current += 1;
+        self.current += expected.len_utf8();
         return true;
     }
 
@@ -89,37 +122,27 @@ impl Scanner {
         if self.is_at_end() {
             return '\0'; // Return null character if at end
         }
-        return self.source.chars().nth(self.current).unwrap();
+        return self.source[self.current..].chars().next().unwrap();
     }
 
     fn peek_next(&self) -> char {
-        if self.current + 1 >= self.source.len() {
-            return '\0'; // Return null character if at end
-        }
-        return self.source.chars().nth(self.current + 1).unwrap();
+        // Return null character if at end
+        return self.source[self.current..].chars().nth(1).unwrap_or('\0');
     }
 
     fn is_digit(&self, c:char) -> bool {
-        match c {
-            '0'..='9' => true,
-            _ => false,
-        }
+        c.is_ascii_digit()
     }
 
     fn is_alpha(&self, c: char) -> bool {
-        match c {
-            'a'..='z' | 'A'..='Z' | '_' => true,
-            _ => false,
-        }
+        matches!(c, 'a'..='z' | 'A'..='Z' | '_')
     }
 
     fn number(&mut self) {
-        let mut has_decimal = false;
         while self.is_digit(self.peek()) {
             self.advance();
         }
         if self.peek() == '.' && self.is_digit(self.peek_next()) {
-            has_decimal = true;
             self.advance();
             while self.is_digit(self.peek()) {self.advance();}
         }
@@ -129,14 +152,14 @@ impl Scanner {
 
     fn string(&mut self) {
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line +=1;
+            let c = self.advance();
+            if c == '\n' {
+                self.new_line();
             }
-            self.advance();
         }
         
         if self.is_at_end() {
-            self.error_manager.borrow_mut().report(self.line, "Unterminated string.", None);
+            self.error("Unterminated string.");
             return;
         }
         else {
@@ -158,13 +181,17 @@ impl Scanner {
     fn scan_token(&mut self)
     {
         self.start = self.current;
+        self.start_column = self.column(self.start);
         let c = self.advance();
         match c {
             '(' => self.add_token(TokenType::LeftParen, None),
             ')' => self.add_token(TokenType::RightParen, None),
             '{' => self.add_token(TokenType::LeftBrace, None),
             '}' => self.add_token(TokenType::RightBrace, None),
+            '[' => self.add_token(TokenType::LeftBracket, None),
+            ']' => self.add_token(TokenType::RightBracket, None),
             ',' => self.add_token(TokenType::Comma, None),
+            ':' => self.add_token(TokenType::Colon, None),
             '.' => self.add_token(TokenType::Dot, None),
             '-' => self.add_token(TokenType::Minus, None),
             '+' => self.add_token(TokenType::Plus, None),
@@ -200,10 +227,17 @@ impl Scanner {
             },
             '/' => {
                 if self.match_char('/') {
+                    // `///` starts a doc comment, but `////` is an ordinary comment
+                    let is_doc = self.peek() == '/' && self.peek_next() != '/';
                     // Single-line comment 
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
+                    if is_doc {
+                        let text = &self.source[self.start + 3..self.current];
+                        let text = text.strip_prefix(' ').unwrap_or(text).trim_end().to_string();
+                        self.add_token(TokenType::DocComment, Some(text));
+                    }
                 }
                 else {
                     self.add_token(TokenType::Slash, None);
@@ -214,20 +248,20 @@ impl Scanner {
                 // Ignore whitespace
             },
             '\n' => {
-                self.line += 1;
+                self.new_line();
             },
             '&' => {
                 if self.match_char('&') {
                     self.add_token(TokenType::And, None);
                 } else {
-                    self.error_manager.borrow_mut().report(self.line, "Unexpected character '&'.", None);
+                    self.error("Unexpected character '&'.");
                 }
             },
             '|' => {
                 if self.match_char('|') {
                     self.add_token(TokenType::Or, None);
                 } else {
-                    self.error_manager.borrow_mut().report(self.line, "Unexpected character '|'.", None);
+                    self.error("Unexpected character '|'.");
                 }
             },
             '"' => {self.string()},
@@ -239,7 +273,7 @@ impl Scanner {
                 self.identifier();
                 }
                 else {
-                self.error_manager.borrow_mut().report(self.line, &format!("Unexpected character: '{}'", c), None);
+                self.error(&format!("Unexpected character: '{}'", c));
                 }
             }
         }
@@ -250,11 +284,11 @@ impl Scanner {
 
 
         while !self.is_at_end() {
-            let start = self.current;
             self.scan_token();
         }
 
-        self.tokens.push(Token::new(TokenType::Eof, "".to_string(), None, self.line));
+        let column = self.column(self.current);
+        self.tokens.push(Token::new(TokenType::Eof, "".to_string(), None, self.line, column));
         return self.tokens.clone();
     
     }
@@ -0,0 +1,135 @@
+use crate::lox::display::fmt_nested;
+use crate::tool::generate_ast::LiteralValue;
+use std::collections::HashMap;
+use std::fmt;
+
+// Hashable projection of a `LiteralValue`.
+// Only nil, booleans, numbers, strings and tuples of those can be hashed.
+// Numbers are compared by value with two adjustments: -0.0 is the same
+// element as 0.0, and every NaN is the same element (unlike `==`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HashKey {
+    Nil,
+    Boolean(bool),
+    Number(u64),
+    String(String),
+    Tuple(Vec<HashKey>),
+}
+
+impl HashKey {
+    pub fn from_value(value: &LiteralValue) -> Option<HashKey> {
+        match value {
+            LiteralValue::Nil => Some(HashKey::Nil),
+            LiteralValue::Boolean(b) => Some(HashKey::Boolean(*b)),
+            LiteralValue::Number(n) if n.is_nan() => Some(HashKey::Number(f64::NAN.to_bits())),
+            LiteralValue::Number(n) if *n == 0.0 => Some(HashKey::Number(0.0f64.to_bits())),
+            LiteralValue::Number(n) => Some(HashKey::Number(n.to_bits())),
+            LiteralValue::String(s) => Some(HashKey::String(s.clone())),
+            LiteralValue::Tuple(elements) => elements.iter()
+                .map(HashKey::from_value)
+                .collect::<Option<Vec<_>>>()
+                .map(HashKey::Tuple),
+            _ => None,
+        }
+    }
+}
+
+// Immutable set of hashable values. Iteration follows insertion order, so
+// printing and converting a set is deterministic.
+#[derive(Clone, Default)]
+pub struct LoxSet {
+    items: Vec<LiteralValue>,
+    index: HashMap<HashKey, usize>,
+}
+
+impl LoxSet {
+    pub fn new() -> Self {
+        LoxSet::default()
+    }
+
+    // Fails with the offending value when it is not hashable
+    pub fn insert(&mut self, value: LiteralValue) -> Result<(), LiteralValue> {
+        let Some(key) = HashKey::from_value(&value) else {
+            return Err(value);
+        };
+        if !self.index.contains_key(&key) {
+            self.index.insert(key, self.items.len());
+            self.items.push(value);
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, value: &LiteralValue) -> bool {
+        HashKey::from_value(value).is_some_and(|key| self.index.contains_key(&key))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    // Pairs with `len`, nothing needs it yet
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn items(&self) -> &[LiteralValue] {
+        &self.items
+    }
+
+    fn filtered(&self, keep: impl Fn(&LiteralValue) -> bool) -> LoxSet {
+        let mut result = LoxSet::new();
+        for item in self.items.iter().filter(|item| keep(item)) {
+            // Items of an existing set are always hashable
+            let _ = result.insert(item.clone());
+        }
+        result
+    }
+
+    pub fn union(&self, other: &LoxSet) -> LoxSet {
+        let mut result = self.clone();
+        for item in &other.items {
+            let _ = result.insert(item.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &LoxSet) -> LoxSet {
+        self.filtered(|item| other.contains(item))
+    }
+
+    pub fn difference(&self, other: &LoxSet) -> LoxSet {
+        self.filtered(|item| !other.contains(item))
+    }
+
+    pub fn is_subset(&self, other: &LoxSet) -> bool {
+        self.items.iter().all(|item| other.contains(item))
+    }
+}
+
+// Sets are equal when they hold the same elements, whatever the order
+impl PartialEq for LoxSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+impl fmt::Debug for LoxSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(&self.items).finish()
+    }
+}
+
+// Prints as `Set(1, "a", (2, 3))`
+impl fmt::Display for LoxSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Set(")?;
+        for (index, item) in self.items.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            fmt_nested(f, item)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,85 @@
+// Index arithmetic shared by indexing and slicing of built-in sequences.
+// Positions count elements: Unicode scalar values for strings, items for tuples.
+
+// Resolves a possibly negative index against a sequence of `len` elements
+pub fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let resolved = if index < 0 { index + len as i64 } else { index };
+    if resolved >= 0 && (resolved as usize) < len {
+        Some(resolved as usize)
+    } else {
+        None
+    }
+}
+
+// Positions selected by `[start:end:step]`, following Python's rules:
+// omitted bounds default to the whole sequence in the direction of `step`,
+// negative bounds count from the end and out-of-range bounds are clamped.
+// `step` must not be zero.
+pub fn slice_positions(len: usize, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let len = len as i64;
+    let clamp = |bound: i64, low: i64, high: i64| {
+        let bound = if bound < 0 { bound + len } else { bound };
+        bound.clamp(low, high)
+    };
+    let mut positions = Vec::new();
+    if step > 0 {
+        let mut current = start.map_or(0, |start| clamp(start, 0, len));
+        let stop = end.map_or(len, |end| clamp(end, 0, len));
+        while current < stop {
+            positions.push(current as usize);
+            // A step too large to add just ends the slice
+            match current.checked_add(step) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+    } else {
+        let mut current = start.map_or(len - 1, |start| clamp(start, -1, len - 1));
+        let stop = end.map_or(-1, |end| clamp(end, -1, len - 1));
+        while current > stop {
+            positions.push(current as usize);
+            // A step too large to add just ends the slice
+            match current.checked_add(step) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative_indexes_count_from_the_end() {
+        assert_eq!(resolve_index(-1, 3), Some(2));
+        assert_eq!(resolve_index(-3, 3), Some(0));
+        assert_eq!(resolve_index(-4, 3), None);
+        assert_eq!(resolve_index(3, 3), None);
+        assert_eq!(resolve_index(i64::MIN, 3), None);
+    }
+
+    #[test]
+    fn bounds_are_clamped() {
+        assert_eq!(slice_positions(4, Some(-10), Some(10), 1), vec![0, 1, 2, 3]);
+        assert_eq!(slice_positions(4, Some(1), Some(-1), 1), vec![1, 2]);
+        assert_eq!(slice_positions(4, Some(3), Some(1), 1), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn negative_steps_walk_backwards() {
+        assert_eq!(slice_positions(4, None, None, -1), vec![3, 2, 1, 0]);
+        assert_eq!(slice_positions(5, Some(-1), Some(0), -2), vec![4, 2]);
+        assert_eq!(slice_positions(4, Some(10), Some(-10), -3), vec![3, 0]);
+    }
+
+    #[test]
+    fn huge_values_do_not_overflow() {
+        assert_eq!(slice_positions(6, Some(1), None, i64::MAX), vec![1]);
+        assert_eq!(slice_positions(6, None, None, i64::MIN), vec![5]);
+        assert_eq!(slice_positions(6, Some(i64::MAX), Some(i64::MIN), -1), vec![5, 4, 3, 2, 1, 0]);
+        assert_eq!(slice_positions(6, Some(i64::MIN), Some(i64::MAX), i64::MAX), vec![0]);
+    }
+}
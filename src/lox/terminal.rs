@@ -0,0 +1,149 @@
+use std::fs::File;
+use std::io::{self, IsTerminal, Read};
+use std::process::{Command, Stdio};
+
+// Terminal access for the `term` built-ins. There is no libc binding in this
+// crate, so modes and sizes go through `stty` on the controlling terminal.
+
+pub fn stdout_is_tty() -> bool {
+    io::stdout().is_terminal()
+}
+
+// Colours are dropped when stdout is redirected or NO_COLOR is set
+pub fn colors_enabled() -> bool {
+    stdout_is_tty() && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty")?)
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!("stty {} failed", args.join(" "))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// (columns, rows) of the terminal, falling back to $COLUMNS/$LINES and then 80x24
+pub fn size() -> (usize, usize) {
+    if let Ok(text) = stty(&["size"]) {
+        if let Some((rows, columns)) = text.split_once(' ') {
+            // A terminal that was never sized (e.g. a fresh pty) reports 0 0
+            if let (Ok(rows @ 1..), Ok(columns @ 1..)) = (rows.parse(), columns.parse()) {
+                return (columns, rows);
+            }
+        }
+    }
+    let from_env = |name: &str, default: usize| std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default);
+    (from_env("COLUMNS", 80), from_env("LINES", 24))
+}
+
+// Keys are read unbuffered and unechoed while this is alive; dropping it
+// puts the terminal back the way it was.
+pub struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    pub fn enable() -> io::Result<RawMode> {
+        let saved = stty(&["-g"])?;
+        // Not full `raw`: output processing stays on so `print` still starts new lines,
+        // and Ctrl-C arrives as a key instead of killing the script mid-mode
+        stty(&["-icanon", "-echo", "-isig", "-ixon", "min", "1", "time", "0"])?;
+        Ok(RawMode { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+// One key press from the terminal (stdin when there is none), as a name:
+// "a", "enter", "up", "ctrl-c", ... None at end of input.
+// Raw mode must already be on for keys to arrive without Enter.
+pub fn read_key() -> io::Result<Option<String>> {
+    let mut input: Box<dyn Read> = match File::open("/dev/tty") {
+        Ok(tty) => Box::new(tty),
+        Err(_) => Box::new(io::stdin()),
+    };
+    let mut byte = [0u8; 1];
+    if input.read(&mut byte)? == 0 {
+        return Ok(None);
+    }
+    let key = match byte[0] {
+        0x1b => escape_sequence(&mut input)?,
+        b'\r' | b'\n' => "enter".to_string(),
+        b'\t' => "tab".to_string(),
+        0x7f | 0x08 => "backspace".to_string(),
+        b' ' => "space".to_string(),
+        control @ 0x01..=0x1a => format!("ctrl-{}", (b'a' + control - 1) as char),
+        first => utf8_char(&mut input, first)?,
+    };
+    Ok(Some(key))
+}
+
+// Arrow and navigation keys arrive as ESC [ x; a lone ESC is the escape key.
+// The rest of a sequence is read with a short timeout so a lone ESC doesn't block.
+fn escape_sequence(input: &mut Box<dyn Read>) -> io::Result<String> {
+    let timed = stty(&["min", "0", "time", "1"]).is_ok();
+    let mut sequence = Vec::new();
+    let mut byte = [0u8; 1];
+    while sequence.len() < 8 && input.read(&mut byte)? == 1 {
+        sequence.push(byte[0]);
+        // A CSI sequence ends with a byte in '@'..='~'
+        if sequence.len() > 1 && (0x40..=0x7e).contains(&byte[0]) {
+            break;
+        }
+    }
+    if timed {
+        stty(&["min", "1", "time", "0"])?;
+    }
+    let name = match sequence.as_slice() {
+        [] => "escape",
+        [b'[', b'A'] | [b'O', b'A'] => "up",
+        [b'[', b'B'] | [b'O', b'B'] => "down",
+        [b'[', b'C'] | [b'O', b'C'] => "right",
+        [b'[', b'D'] | [b'O', b'D'] => "left",
+        [b'[', b'H'] | [b'O', b'H'] | [b'[', b'1', b'~'] => "home",
+        [b'[', b'F'] | [b'O', b'F'] | [b'[', b'4', b'~'] => "end",
+        [b'[', b'3', b'~'] => "delete",
+        [b'[', b'5', b'~'] => "page_up",
+        [b'[', b'6', b'~'] => "page_down",
+        _ => "unknown",
+    };
+    Ok(name.to_string())
+}
+
+fn utf8_char(input: &mut Box<dyn Read>, first: u8) -> io::Result<String> {
+    let length = match first {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    };
+    let mut bytes = vec![first; length];
+    input.read_exact(&mut bytes[1..])?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// What a script changed on the terminal, undone when the interpreter goes away
+#[derive(Default)]
+pub struct TerminalState {
+    pub raw_mode: Option<RawMode>,
+    pub cursor_hidden: bool,
+}
+
+impl Drop for TerminalState {
+    fn drop(&mut self) {
+        if self.cursor_hidden {
+            use std::io::Write;
+            let mut stdout = io::stdout();
+            let _ = write!(stdout, "\x1b[?25h");
+            let _ = stdout.flush();
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub use crate::lox::token_type::TokenType;
+use std::fmt;
 
 // Define the Token struct
 #[derive(Debug, Clone)]
@@ -7,19 +8,25 @@ pub struct Token {
     pub lexeme: String,
     pub literal: Option<String>,
     pub line: usize,
+    // 1-based, counted in characters from the start of the line
+    pub column: usize,
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, lexeme: String, literal: Option<String>, line: usize) -> Self {
+    pub fn new(token_type: TokenType, lexeme: String, literal: Option<String>, line: usize, column: usize) -> Self {
         Self {
             token_type,
             lexeme,
             literal,
             line,
+            column,
         }
     }
-    pub fn to_string(&self) -> String {
-        format!("{:?} {} {:?}", self.token_type, self.lexeme, self.literal)
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} {} {:?}", self.token_type, self.lexeme, self.literal)
     }
 }
 
@@ -7,7 +7,10 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
     Dot,
     Minus,
     Plus,
@@ -29,24 +32,36 @@ pub enum TokenType {
     Identifier,
     StringLiteral,
     NumberLiteral,
+    DocComment, // `/// text`, the text is the literal
 
     // Keywords
     And,
+    Assert,
+    Break,
     Class,
+    Const,
+    Continue,
+    Defer,
+    Do,
     Else,
+    Enum,
     False,
     For,
     Fun,
     If,
+    In,
+    Is,
     Nil,
     Or,
     Print,
+    Record,
     Return,
     Super,
     This,
     True,
     Var,
     While,
+    With,
 
     Eof, // End of file
 }
@@ -0,0 +1,403 @@
+use crate::lox::error_manager::ErrorManager;
+use crate::tool::generate_ast::{Expr, LiteralValue, Stmt};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+// Static type of an expression. `Any` is the gradual escape hatch: it is
+// compatible with everything, and it is what an unannotated variable has
+// unless its initializer is a literal.
+#[derive(Debug, Clone, PartialEq)]
+enum Type {
+    Any,
+    Number,
+    String,
+    Boolean,
+    Nil,
+    Tuple,
+    Function(Option<Rc<Signature>>),   // `None` for the bare `Function` annotation
+    RecordConstructor(String, usize),  // the constructor, with its number of fields
+    EnumNamespace(String),
+    Record(String),                    // a value of a declared record or enum
+}
+
+#[derive(Debug, PartialEq)]
+struct Signature {
+    parameters: Vec<Type>,
+    return_type: Type,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "Any"),
+            Type::Number => write!(f, "Number"),
+            Type::String => write!(f, "String"),
+            Type::Boolean => write!(f, "Boolean"),
+            Type::Nil => write!(f, "Nil"),
+            Type::Tuple => write!(f, "Tuple"),
+            Type::Function(_) => write!(f, "Function"),
+            Type::RecordConstructor(name, _) => write!(f, "record {}", name),
+            Type::EnumNamespace(name) => write!(f, "enum {}", name),
+            Type::Record(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Type {
+    fn is_known(&self) -> bool {
+        *self != Type::Any
+    }
+
+    // Whether a value of type `actual` can be stored where `self` is expected
+    fn accepts(&self, actual: &Type) -> bool {
+        match (self, actual) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Function(None), Type::Function(_)) => true,
+            _ => self == actual,
+        }
+    }
+}
+
+// Optional pass run by `lox check --types`, after the resolver.
+// It only reports mismatches it can prove: anything involving `Any` passes.
+pub struct TypeChecker {
+    scopes: Vec<HashMap<String, Type>>,
+    // Record and enum names usable in annotations
+    declared_types: HashSet<String>,
+    // Declared return type of each enclosing function, innermost last
+    return_types: Vec<(String, Type)>,
+    error_manager: Rc<RefCell<ErrorManager>>,
+}
+
+impl TypeChecker {
+    pub fn new(error_manager: Rc<RefCell<ErrorManager>>) -> Self {
+        TypeChecker {
+            scopes: vec![HashMap::new()],
+            declared_types: HashSet::new(),
+            return_types: Vec::new(),
+            error_manager,
+        }
+    }
+
+    pub fn check(&mut self, statements: &[Stmt]) {
+        // Types can be named in annotations before their declaration
+        self.collect_declared_types(statements);
+        self.check_statements(statements);
+    }
+
+    fn collect_declared_types(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            match statement {
+                Stmt::Record { name, .. } | Stmt::Enum { name, .. } => {
+                    self.declared_types.insert(name.clone());
+                },
+                Stmt::Block(statements) | Stmt::Sequence(statements) | Stmt::Function { body: statements, .. }
+                | Stmt::Test { body: statements, .. } => {
+                    self.collect_declared_types(statements);
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn error(&mut self, line: usize, message: &str) {
+        self.error_manager.borrow_mut().report(line, &format!("Type error: {}", message), None);
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: &str, value_type: Type) {
+        let scope = self.scopes.last_mut().expect("Type checker always has a global scope");
+        scope.insert(name.to_string(), value_type);
+    }
+
+    fn lookup(&self, name: &str) -> Type {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name)).cloned().unwrap_or(Type::Any)
+    }
+
+    fn annotation(&mut self, annotation: &Option<String>, line: usize) -> Type {
+        let Some(name) = annotation else {
+            return Type::Any;
+        };
+        match name.as_str() {
+            "Any" => Type::Any,
+            "Number" => Type::Number,
+            "String" => Type::String,
+            "Boolean" => Type::Boolean,
+            "Nil" | "nil" => Type::Nil,
+            "Tuple" => Type::Tuple,
+            "Function" => Type::Function(None),
+            _ if self.declared_types.contains(name) => Type::Record(name.clone()),
+            _ => {
+                self.error(line, &format!("Unknown type '{}'.", name));
+                Type::Any
+            },
+        }
+    }
+
+    fn expect(&mut self, expected: &Type, actual: &Type, line: usize, context: &str) {
+        if !expected.accepts(actual) {
+            self.error(line, &format!("{} expects {} but got {}.", context, expected, actual));
+        }
+    }
+
+    fn check_statements(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.check_statement(statement);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Stmt) {
+        match statement {
+            Stmt::Expression(expr) | Stmt::Print(expr) => {
+                self.check_expression(expr);
+            },
+            Stmt::Var { name, type_annotation, initializer, line, .. } => {
+                let mut declared = self.annotation(type_annotation, *line);
+                if let Some(initializer) = initializer {
+                    let actual = self.check_expression(initializer);
+                    self.expect(&declared, &actual, *line, &format!("Variable '{}'", name));
+                    // `var x = 1;` is a Number from then on, like `var x: Number = 1;`.
+                    // Other unannotated variables can hold anything later on.
+                    if type_annotation.is_none() {
+                        declared = Self::literal_type(initializer);
+                    }
+                }
+                self.bind(name, declared);
+            },
+            Stmt::Const { name, type_annotation, initializer, line, .. } => {
+                let declared = self.annotation(type_annotation, *line);
+                let actual = self.check_expression(initializer);
+                self.expect(&declared, &actual, *line, &format!("Constant '{}'", name));
+                // A constant keeps its initializer's type forever
+                let value_type = if declared.is_known() { declared } else { actual };
+                self.bind(name, value_type);
+            },
+            Stmt::Destructure { names, initializer, .. } => {
+                self.check_expression(initializer);
+                for name in names {
+                    self.bind(name, Type::Any);
+                }
+            },
+            Stmt::Function { name, parameters, return_type, body, line, .. } => {
+                let parameter_types: Vec<Type> = parameters.iter()
+                    .map(|(_, annotation)| self.annotation(annotation, *line))
+                    .collect();
+                let return_type = self.annotation(return_type, *line);
+                let signature = Signature { parameters: parameter_types.clone(), return_type: return_type.clone() };
+                // Bound before the body so that recursive calls are checked too
+                self.bind(name, Type::Function(Some(Rc::new(signature))));
+                self.begin_scope();
+                for ((parameter, _), parameter_type) in parameters.iter().zip(parameter_types) {
+                    self.bind(parameter, parameter_type);
+                }
+                self.return_types.push((name.clone(), return_type));
+                self.check_statements(body);
+                self.return_types.pop();
+                self.end_scope();
+            },
+            Stmt::Return { value, line, .. } => {
+                let actual = match value {
+                    Some(value) => self.check_expression(value),
+                    None => Type::Nil,
+                };
+                if let Some((name, expected)) = self.return_types.last().cloned() {
+                    self.expect(&expected, &actual, *line, &format!("Return value of '{}'", name));
+                }
+            },
+            Stmt::Record { name, fields, .. } => self.bind(name, Type::RecordConstructor(name.clone(), fields.len())),
+            Stmt::Enum { name, .. } => self.bind(name, Type::EnumNamespace(name.clone())),
+            Stmt::Block(statements) | Stmt::Test { body: statements, .. } => {
+                self.begin_scope();
+                self.check_statements(statements);
+                self.end_scope();
+            },
+            Stmt::Assert { condition, message, line } => {
+                let actual = self.check_expression(condition);
+                self.expect(&Type::Boolean, &actual, *line, "Assertion");
+                if let Some(message) = message {
+                    self.check_expression(message);
+                }
+            },
+            Stmt::Defer { statement, .. } => self.check_statement(statement),
+            Stmt::Sequence(statements) => self.check_statements(statements),
+            Stmt::If { condition, then_branch, else_branch } => {
+                self.check_expression(condition);
+                self.check_statement(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.check_statement(else_branch);
+                }
+            },
+            Stmt::While { condition, body, increment, .. } => {
+                self.check_expression(condition);
+                self.check_statement(body);
+                if let Some(increment) = increment {
+                    self.check_expression(increment);
+                }
+            },
+            Stmt::DoWhile { body, condition, .. } => {
+                self.check_statement(body);
+                self.check_expression(condition);
+            },
+            _ => {},
+        }
+    }
+
+    // Type of a number, string or boolean literal; `nil` usually stands in for a later value
+    fn literal_type(expression: &Expr) -> Type {
+        match expression {
+            Expr::Literal { value: LiteralValue::Number(_) } => Type::Number,
+            Expr::Literal { value: LiteralValue::String(_) } => Type::String,
+            Expr::Literal { value: LiteralValue::Boolean(_) } => Type::Boolean,
+            Expr::Grouping { expression } => Self::literal_type(expression),
+            _ => Type::Any,
+        }
+    }
+
+    fn check_expression(&mut self, expression: &Expr) -> Type {
+        match expression {
+            Expr::Literal { value } => match value {
+                LiteralValue::Number(_) => Type::Number,
+                LiteralValue::String(_) => Type::String,
+                LiteralValue::Boolean(_) => Type::Boolean,
+                LiteralValue::Nil => Type::Nil,
+                LiteralValue::Tuple(_) => Type::Tuple,
+                _ => Type::Any,
+            },
+            Expr::Grouping { expression } => self.check_expression(expression),
+            Expr::Unary { operator, right, line } => {
+                let operand = self.check_expression(right);
+                let expected = if operator == "!" { Type::Boolean } else { Type::Number };
+                self.expect(&expected, &operand, *line, &format!("Operator '{}'", operator));
+                expected
+            },
+            Expr::Binary { left, operator, right, line } => {
+                let left_type = self.check_expression(left);
+                let right_type = self.check_expression(right);
+                match operator.as_str() {
+                    "==" | "!=" | "in" => Type::Boolean,
+                    "+" if left_type == Type::String || right_type == Type::String => {
+                        self.expect(&Type::String, &left_type, *line, "Operator '+'");
+                        self.expect(&Type::String, &right_type, *line, "Operator '+'");
+                        Type::String
+                    },
+                    "<" | "<=" | ">" | ">=" => {
+                        self.expect(&Type::Number, &left_type, *line, &format!("Operator '{}'", operator));
+                        self.expect(&Type::Number, &right_type, *line, &format!("Operator '{}'", operator));
+                        Type::Boolean
+                    },
+                    _ => {
+                        self.expect(&Type::Number, &left_type, *line, &format!("Operator '{}'", operator));
+                        self.expect(&Type::Number, &right_type, *line, &format!("Operator '{}'", operator));
+                        Type::Number
+                    },
+                }
+            },
+            Expr::Logical { left, right, .. } => {
+                self.check_expression(left);
+                self.check_expression(right);
+                Type::Boolean
+            },
+            Expr::Variable { name } => self.lookup(name),
+            Expr::Assignment { name, value, line } => {
+                let actual = self.check_expression(value);
+                let declared = self.lookup(name);
+                self.expect(&declared, &actual, *line, &format!("Variable '{}'", name));
+                actual
+            },
+            Expr::Tuple { elements } => {
+                for element in elements {
+                    self.check_expression(element);
+                }
+                Type::Tuple
+            },
+            Expr::Map { entries, .. } => {
+                for (key, value) in entries {
+                    self.check_expression(key);
+                    self.check_expression(value);
+                }
+                Type::Any
+            },
+            Expr::TupleAssignment { value, line, .. } => {
+                let actual = self.check_expression(value);
+                self.expect(&Type::Tuple, &actual, *line, "Destructuring assignment");
+                actual
+            },
+            Expr::Call { callee, arguments, named_arguments, line } => {
+                let callee_type = self.check_expression(callee);
+                let argument_types: Vec<Type> = arguments.iter().map(|argument| self.check_expression(argument)).collect();
+                for (_, argument) in named_arguments {
+                    self.check_expression(argument);
+                }
+                // Named arguments are only counted, signatures don't record parameter names
+                let argument_count = argument_types.len() + named_arguments.len();
+                match callee_type {
+                    Type::Function(Some(signature)) => {
+                        if signature.parameters.len() != argument_count {
+                            self.error(*line, &format!("Expected {} arguments but got {}.",
+                                signature.parameters.len(), argument_count));
+                        }
+                        for (index, (expected, actual)) in signature.parameters.iter().zip(&argument_types).enumerate() {
+                            self.expect(expected, actual, *line, &format!("Argument {}", index + 1));
+                        }
+                        signature.return_type.clone()
+                    },
+                    Type::RecordConstructor(name, arity) => {
+                        if arity != argument_count {
+                            self.error(*line, &format!("Record '{}' expects {} arguments but got {}.",
+                                name, arity, argument_count));
+                        }
+                        Type::Record(name)
+                    },
+                    Type::Any | Type::Function(None) => Type::Any,
+                    other => {
+                        self.error(*line, &format!("Cannot call a value of type {}.", other));
+                        Type::Any
+                    },
+                }
+            },
+            Expr::Get { object, .. } => {
+                self.check_expression(object);
+                Type::Any
+            },
+            Expr::Index { object, index, line } => {
+                let object_type = self.check_expression(object);
+                let index_type = self.check_expression(index);
+                // Maps have no static type and take keys of any kind
+                if matches!(object_type, Type::Tuple | Type::String) {
+                    self.expect(&Type::Number, &index_type, *line, "Index");
+                }
+                Type::Any
+            },
+            Expr::Slice { object, start, end, step, line } => {
+                let object_type = self.check_expression(object);
+                for part in [start, end, step].into_iter().flatten() {
+                    let part_type = self.check_expression(part);
+                    self.expect(&Type::Number, &part_type, *line, "Slice bound");
+                }
+                // Slicing returns a value of the same kind
+                object_type
+            },
+            Expr::With { object, updates, .. } => {
+                let object_type = self.check_expression(object);
+                for (_, update) in updates {
+                    self.check_expression(update);
+                }
+                object_type
+            },
+            Expr::Is { value, kind, .. } => {
+                self.check_expression(value);
+                self.check_expression(kind);
+                Type::Boolean
+            },
+        }
+    }
+}
@@ -1,8 +1,9 @@
+#![allow(clippy::needless_return)]
+
 mod lox;
 mod tool;
 
 fn main() {
     let mut interpreter = lox::lexer::Lexer::new();
    interpreter.main();
-   tool::ast_printer::main();
 }
\ No newline at end of file
@@ -1,4 +1,3 @@
-use crate::lox::token::{Token, TokenType};
 use crate::tool::generate_ast::{Expr, LiteralValue};
 pub struct AstPrinter;
 
@@ -9,7 +8,7 @@ impl AstPrinter {
 
     pub fn print(&self, expr: &Expr) -> String {
         match expr {
-            Expr::Binary { left, operator, right } => {
+            Expr::Binary { left, operator, right, .. } => {
                 format!("({} {} {})", operator, self.print(left), self.print(right))
             }
             Expr::Grouping { expression } => {
@@ -17,25 +16,54 @@ impl AstPrinter {
             }
 
             Expr::Literal { value } => {
-                match value {
-                    LiteralValue::Number(n) => n.to_string(),
-                    LiteralValue::String(s) => format!("\"{}\"", s),
-                    LiteralValue::Boolean(b) => b.to_string(),
-                    LiteralValue::Nil => "nil".to_string(),
-                }
-            }
-            Expr::Unary { operator, right } => {
+                value.repr().to_string()
+            }
+            Expr::Unary { operator, right, .. } => {
                 format!("({} {})", operator, self.print(right))
             }
             Expr::Variable { name } => {
                 name.clone()
             }
-            Expr::Assignment { name, value } => {
+            Expr::Assignment { name, value, .. } => {
                 format!("({} = {})", name, self.print(value))
             }
             Expr::Logical { left, operator, right } => {
                 format!("({} {} {})", operator, self.print(left), self.print(right))
             }
+            Expr::Tuple { elements } => {
+                let elements: Vec<String> = elements.iter().map(|e| self.print(e)).collect();
+                format!("(tuple {})", elements.join(" "))
+            }
+            Expr::Map { entries, .. } => {
+                let entries: Vec<String> = entries.iter().map(|(k, v)| format!("{}: {}", self.print(k), self.print(v))).collect();
+                format!("(map {})", entries.join(" "))
+            }
+            Expr::Call { callee, arguments, named_arguments, .. } => {
+                let arguments: Vec<String> = arguments.iter().map(|e| self.print(e))
+                    .chain(named_arguments.iter().map(|(name, e)| format!("{}: {}", name, self.print(e))))
+                    .collect();
+                format!("(call {} {})", self.print(callee), arguments.join(" "))
+            }
+            Expr::Get { object, name, .. } => {
+                format!("(. {} {})", self.print(object), name)
+            }
+            Expr::Index { object, index, .. } => {
+                format!("(index {} {})", self.print(object), self.print(index))
+            }
+            Expr::Slice { object, start, end, step, .. } => {
+                let part = |part: &Option<Box<Expr>>| part.as_ref().map_or("_".to_string(), |e| self.print(e));
+                format!("(slice {} {} {} {})", self.print(object), part(start), part(end), part(step))
+            }
+            Expr::With { object, updates, .. } => {
+                let updates: Vec<String> = updates.iter().map(|(name, e)| format!("{}: {}", name, self.print(e))).collect();
+                format!("(with {} {})", self.print(object), updates.join(" "))
+            }
+            Expr::Is { value, kind, .. } => {
+                format!("(is {} {})", self.print(value), self.print(kind))
+            }
+            Expr::TupleAssignment { names, value, .. } => {
+                format!("(({}) = {})", names.join(", "), self.print(value))
+            }
         }
     }
     pub fn main() {
@@ -45,6 +73,7 @@ impl AstPrinter {
             right: Box::new(Expr::Literal {
                 value: LiteralValue::Number(123.0),
             }),
+            line: 1,
         }),
         operator: "*".to_string(),
         right: Box::new(Expr::Grouping {
@@ -52,6 +81,7 @@ impl AstPrinter {
                 value: LiteralValue::Number(45.67),
             }),
         }),
+        line: 1,
     };
 
     let printer = AstPrinter::new();
@@ -67,6 +97,7 @@ pub fn main() {
             right: Box::new(Expr::Literal {
                 value: LiteralValue::Number(123.0),
             }),
+            line: 1,
         }),
         operator: "*".to_string(),
         right: Box::new(Expr::Grouping {
@@ -74,6 +105,7 @@ pub fn main() {
                 value: LiteralValue::Number(45.67),
             }),
         }),
+        line: 1,
     };
 
     let printer = AstPrinter::new();
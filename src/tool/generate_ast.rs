@@ -1,9 +1,30 @@
+use crate::lox::function::LoxFunction;
+use crate::lox::native::{BoundMethod, NativeFunction};
+use crate::lox::set::LoxSet;
+use crate::lox::map::LoxMap;
+use crate::lox::namespace::Namespace;
+use crate::lox::enumeration::{EnumType, VariantType, VariantValue};
+use crate::lox::record::{RecordInstance, RecordType};
+use std::rc::Rc;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum LiteralValue {
     Number(f64),
     String(String),
     Boolean(bool),
     Nil,
+    Tuple(Vec<LiteralValue>), // immutable, compared element by element
+    RecordType(Rc<RecordType>), // the constructor bound to a record's name
+    Record(Rc<RecordInstance>),
+    Enum(Rc<EnumType>),
+    VariantConstructor(Rc<VariantType>), // Shape.Circle, waiting for its payload
+    Variant(Rc<VariantValue>),
+    Function(Rc<LoxFunction>),
+    Native(Rc<NativeFunction>),
+    BoundMethod(Rc<BoundMethod>), // s.union, waiting for its arguments
+    Set(Rc<LoxSet>),
+    Map(Rc<LoxMap>), // shared and mutable, compared by identity
+    Namespace(Rc<Namespace>), // math
 }
 
 #[derive(Debug, Clone)]
@@ -12,6 +33,7 @@ pub enum Expr{
         left: Box<Expr>,
         operator: String,
         right: Box<Expr>,
+        line: usize,
     },
     Grouping{ // (xxx)
         expression: Box<Expr>,
@@ -22,6 +44,7 @@ pub enum Expr{
     Unary{ // -1, !true
         operator: String,
         right: Box<Expr>,
+        line: usize,
     },
     Variable{ // x
         name: String,
@@ -29,12 +52,54 @@ pub enum Expr{
     Assignment{ // x = 1
         name: String,
         value: Box<Expr>,
+        line: usize,
     },
     Logical {
         left: Box<Expr>,
         operator: String,
         right: Box<Expr>,
     },
+    Tuple{ // (1, 2), (1,), ()
+        elements: Vec<Expr>,
+    },
+    Map{ // {"a": 1, "b": 2}, {}
+        entries: Vec<(Expr, Expr)>,
+    },
+    Call{ // Point(1, y: 2)
+        callee: Box<Expr>,
+        arguments: Vec<Expr>,
+        named_arguments: Vec<(String, Expr)>, // always after the positional ones
+        line: usize,
+    },
+    Get{ // p.x
+        object: Box<Expr>,
+        name: String,
+    },
+    Index{ // s[0], s[-1]
+        object: Box<Expr>,
+        index: Box<Expr>,
+        line: usize,
+    },
+    Slice{ // s[start:end:step], every part optional
+        object: Box<Expr>,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+        step: Option<Box<Expr>>,
+        line: usize,
+    },
+    With{ // p with { x: 3 }
+        object: Box<Expr>,
+        updates: Vec<(String, Expr)>,
+    },
+    Is{ // s is Shape.Circle, p is Point
+        value: Box<Expr>,
+        kind: Box<Expr>,
+    },
+    TupleAssignment{ // (a, b) = (b, a)
+        names: Vec<String>,
+        value: Box<Expr>,
+        line: usize,
+    },
 }
 #[derive(Debug, Clone)]
 pub enum Stmt {
@@ -42,7 +107,22 @@ pub enum Stmt {
     Print(Expr),
     Var {
         name: String,
+        type_annotation: Option<String>, // var x: Number = 1;
         initializer: Option<Expr>,
+        line: usize,
+        doc: Option<String>, // from the `///` comments right above
+    },
+    Destructure { // var (x, y) = pair;
+        names: Vec<String>,
+        initializer: Expr,
+        line: usize,
+    },
+    Const { // const x = 1;
+        name: String,
+        type_annotation: Option<String>,
+        initializer: Expr,
+        line: usize,
+        doc: Option<String>,
     },
     Block(Vec<Stmt>),
     If {
@@ -53,46 +133,59 @@ pub enum Stmt {
     While {
         condition: Expr,
         body: Box<Stmt>,
+        increment: Option<Expr>, // run after the body, even on `continue`
+        label: Option<String>,
+    },
+    DoWhile { // do { } while (cond);
+        body: Box<Stmt>,
+        condition: Expr,
+        label: Option<String>,
     },
-    Function {
+    Function { // fun add(a: Number, b): Number { ... }
         name: String,
-        parameters: Vec<String>,
+        parameters: Vec<(String, Option<String>)>,
+        return_type: Option<String>,
         body: Vec<Stmt>,
+        line: usize,
+        doc: Option<String>,
     },
     Return {
-        keyword: String,
         value: Option<Expr>,
+        line: usize,
     },
-    Class {
+    Record { // record Point(x, y);
         name: String,
-        superclass: Option<String>,
-        methods: Vec<Stmt>,
+        fields: Vec<String>,
+        line: usize,
+        doc: Option<String>,
     },
-    Break,
-    Continue,
-    Empty,
-    Error(String), // For error handling
+    Enum { // enum Shape { Circle(r), Rect(w, h), Empty }
+        name: String,
+        variants: Vec<(String, Vec<String>)>,
+        line: usize,
+        doc: Option<String>,
+    },
+    Assert { // assert x == 1, "message";
+        condition: Expr,
+        message: Option<Expr>,
+        line: usize,
+    },
+    Defer { // defer print "done";, runs when the enclosing block exits
+        statement: Box<Stmt>,
+        line: usize,
+    },
+    Test { // test "name" { ... }, top level only, skipped by a normal run
+        name: String,
+        body: Vec<Stmt>,
+    },
+    Break {
+        label: Option<String>,
+        line: usize,
+    },
+    Continue {
+        label: Option<String>,
+        line: usize,
+    },
+    Error(#[allow(dead_code)] String), // For error handling, the message shows in Debug output
     Sequence(Vec<Stmt>),
 }
-
-pub struct Ast {
-    
-    pub ast: String,
-    pub statements_list: Vec<Stmt>,
-}
-
-
-impl Ast {
-    pub fn new(statements_list: Vec<Stmt>) -> Self {
-        Ast {
-            ast: String::new(),
-            statements_list,
-        }
-    }
-
-    pub fn generate_ast(&mut self) {
-        for stmt in &self.statements_list {
-            println!()
-            }
-        }
-    }
\ No newline at end of file
@@ -1,2 +1,4 @@
 pub mod generate_ast;
+// Debugging aid for the parser, not reachable from the command line
+#[allow(dead_code)]
 pub mod ast_printer;
\ No newline at end of file
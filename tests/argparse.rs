@@ -0,0 +1,104 @@
+mod common;
+
+use common::{run, run_with};
+
+const GREET: &str = r#"
+    var opts = argparse.parse({
+        "program": "greet", "about": "Says hello",
+        "flags": ({"name": "loud", "short": "l", "help": "Shout"},),
+        "options": ({"name": "times", "short": "n", "type": "number", "default": 1, "help": "Repeat count"},),
+        "positionals": ({"name": "who", "default": "world"},),
+    });
+    print opts;
+"#;
+
+const TOOL: &str = r#"
+    print argparse.parse({
+        "program": "tool",
+        "flags": ({"name": "verbose", "short": "v"},),
+        "commands": {
+            "add": {"positionals": ({"name": "item"},), "options": ({"name": "tag", "required": true},)},
+            "list": {},
+        },
+    });
+"#;
+
+#[test]
+fn flags_options_and_positionals() {
+    let cases: [(&str, &[&str], &str); 3] = [
+        ("argparse_defaults", &[], "{\"loud\": false, \"times\": 1, \"who\": \"world\"}\n"),
+        ("argparse_short", &["-l", "-n", "3", "ann"], "{\"loud\": true, \"times\": 3, \"who\": \"ann\"}\n"),
+        ("argparse_long", &["--times=2", "--", "-x"], "{\"loud\": false, \"times\": 2, \"who\": \"-x\"}\n"),
+    ];
+    for (name, argv, expected) in cases {
+        let result = run_with(name, GREET, &[], argv);
+        assert_eq!(result.stderr, "", "{}", name);
+        assert_eq!(result.stdout, expected, "{}", name);
+    }
+}
+
+#[test]
+fn help_is_generated_and_exits_cleanly() {
+    let result = run_with("argparse_help", GREET, &[], &["--help"]);
+    assert_eq!(result.code, Some(0));
+    assert_eq!(result.stdout, "greet - Says hello
+
+Usage: greet [options] [who]
+
+Arguments:
+  [who]  (default: \"world\")
+
+Options:
+  -l, --loud           Shout
+  -n, --times <times>  Repeat count (default: 1)
+  -h, --help           Show this help and exit
+");
+}
+
+#[test]
+fn invalid_input_is_a_usage_error() {
+    let cases: [(&str, &[&str], &str); 3] = [
+        ("argparse_number", &["-n", "x"], "greet: error: option '-n' expects a number but got 'x'\n"),
+        ("argparse_unknown", &["--bogus"], "greet: error: unknown option '--bogus'\n"),
+        ("argparse_extra", &["a", "b"], "greet: error: unexpected argument 'b'\n"),
+    ];
+    for (name, argv, message) in cases {
+        let result = run_with(name, GREET, &[], argv);
+        assert_eq!(result.code, Some(64), "{}", name);
+        assert_eq!(result.stdout, "", "{}", name);
+        assert_eq!(result.stderr, format!("{}Usage: greet [options] [who]\nRun 'greet --help' for more information.\n", message), "{}", name);
+    }
+}
+
+#[test]
+fn subcommands() {
+    let result = run_with("argparse_command", TOOL, &[], &["-v", "add", "x", "--tag", "t"]);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "{\"verbose\": true, \"command\": \"add\", \"tag\": \"t\", \"item\": \"x\"}\n");
+
+    let result = run_with("argparse_missing_option", TOOL, &[], &["add", "x"]);
+    assert_eq!(result.code, Some(64));
+    assert!(result.stderr.starts_with("tool add: error: missing required option '--tag'\n"), "{}", result.stderr);
+
+    let result = run_with("argparse_missing_command", TOOL, &[], &[]);
+    assert_eq!(result.code, Some(64));
+    assert!(result.stderr.starts_with("tool: error: missing command\n"), "{}", result.stderr);
+
+    let result = run_with("argparse_command_help", TOOL, &[], &["add", "--help"]);
+    assert_eq!(result.code, Some(0));
+    assert!(result.stdout.starts_with("Usage: tool add [options] --tag <tag> <item>\n"), "{}", result.stdout);
+}
+
+#[test]
+fn mistakes_in_the_spec_are_runtime_errors() {
+    let cases = [
+        ("argparse_twice", "argparse.parse({\"flags\": ({\"name\": \"a\"}, {\"name\": \"a\"})}, ());", "argparse: 'a' is declared twice."),
+        ("argparse_key", "argparse.parse({\"wat\": 1}, ());", "argparse: unknown key \"wat\" in the spec"),
+        ("argparse_argv", "argparse.parse({}, (1,));", "argparse.parse: arguments must be strings, got 1."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
@@ -0,0 +1,61 @@
+// Not every test file uses every helper
+#![allow(dead_code)]
+
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+pub struct Run {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+impl From<Output> for Run {
+    fn from(output: Output) -> Self {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // The interpreter reports a clean run on stdout after the script's own output
+        let stdout = stdout.strip_suffix("Lox code executed successfully.\n").unwrap_or(&stdout);
+        Run {
+            stdout: stdout.to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            code: output.status.code(),
+        }
+    }
+}
+
+// Runs `source` as a script file through the interpreter binary
+pub fn run(name: &str, source: &str) -> Run {
+    run_with(name, source, &[], &[])
+}
+
+// Like `run`, with interpreter `options` before the script path and script `args` after it
+pub fn run_with(name: &str, source: &str, options: &[&str], args: &[&str]) -> Run {
+    let path = std::env::temp_dir().join(format!("lux-{}-{}.lox", std::process::id(), name));
+    std::fs::write(&path, source).expect("write script");
+    let output = Command::new(env!("CARGO_BIN_EXE_lux_interpreter"))
+        .args(options)
+        .arg(&path)
+        .args(args)
+        .output()
+        .expect("run interpreter");
+    let _ = std::fs::remove_file(&path);
+    output.into()
+}
+
+// Runs the interpreter with `args` as they are, e.g. `lox test <dir>`
+pub fn lox<S: AsRef<OsStr>>(args: &[S]) -> Run {
+    Command::new(env!("CARGO_BIN_EXE_lux_interpreter"))
+        .args(args)
+        .output()
+        .expect("run interpreter")
+        .into()
+}
+
+// A fresh, empty directory for one test
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lux-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create scratch directory");
+    dir
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn redeclaring_a_constant_in_its_scope_is_rejected() {
+    let result = run("const_redeclare", "const x = 1;\nvar x = 2;\nx = 3;\nprint x;\n");
+    assert_eq!(result.code, Some(65));
+    assert_eq!(result.stdout, "");
+    assert!(result.stderr.contains("[line 2] Error: Cannot redeclare constant 'x' declared on line 1."), "{}", result.stderr);
+}
+
+#[test]
+fn redeclaring_a_constant_as_a_constant_is_rejected() {
+    let result = run("const_redeclare_const", "const x = 1;\nconst x = 2;\n");
+    assert_eq!(result.code, Some(65));
+    assert!(result.stderr.contains("Cannot redeclare constant 'x' declared on line 1."), "{}", result.stderr);
+}
+
+#[test]
+fn assigning_to_a_constant_is_rejected() {
+    let result = run("const_assign", "const x = 1;\nx = 2;\n");
+    assert_eq!(result.code, Some(65));
+    assert!(result.stderr.contains("[line 2] Error: Cannot assign to constant 'x' declared on line 1."), "{}", result.stderr);
+}
+
+#[test]
+fn a_constant_can_be_shadowed_in_an_inner_block() {
+    let result = run("const_shadow", "const x = 1;\n{\n    var x = 2;\n    x = 3;\n    print x;\n}\nprint x;\n");
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "3\n1\n");
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn defer_runs_at_the_end_of_each_iteration() {
+    let result = run("defer_iteration", r#"
+        fun f() {
+            var i = 0;
+            while (i < 3) {
+                i = i + 1;
+                defer print i;
+                print "iter";
+            }
+            print "end";
+        }
+        f();
+    "#);
+    assert_eq!(result.stdout, "iter\n1\niter\n2\niter\n3\nend\n");
+}
+
+#[test]
+fn defer_in_a_top_level_loop_body() {
+    let result = run("defer_top_level_loop", r#"
+        var i = 0;
+        do {
+            i = i + 1;
+            defer print "done " + str(i);
+        } while (i < 2);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "done 1\ndone 2\n");
+}
+
+#[test]
+fn defer_runs_on_break_and_continue() {
+    let result = run("defer_jumps", r#"
+        var i = 0;
+        while (true) {
+            i = i + 1;
+            defer print "leave " + str(i);
+            if (i == 1) {
+                continue;
+            }
+            if (i == 3) {
+                break;
+            }
+            print "body " + str(i);
+        }
+        print "after";
+    "#);
+    assert_eq!(result.stdout, "leave 1\nbody 2\nleave 2\nleave 3\nafter\n");
+}
+
+#[test]
+fn defer_in_an_if_body_runs_when_the_body_exits() {
+    let result = run("defer_if", r#"
+        fun f(flag) {
+            if (flag) {
+                defer print "then done";
+                print "then";
+            } else {
+                defer print "else done";
+                print "else";
+            }
+            print "after if";
+        }
+        f(true);
+        f(false);
+    "#);
+    assert_eq!(result.stdout, "then\nthen done\nafter if\nelse\nelse done\nafter if\n");
+}
+
+#[test]
+fn defer_outside_of_any_body_is_rejected() {
+    let result = run("defer_top_level", "defer print 1;\n");
+    assert_eq!(result.code, Some(65));
+    assert_eq!(result.stdout, "");
+    assert!(result.stderr.contains("Cannot use 'defer' outside of a block."), "{}", result.stderr);
+}
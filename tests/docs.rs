@@ -0,0 +1,105 @@
+mod common;
+
+use common::{lox, scratch_dir};
+use std::path::Path;
+
+const SOURCE: &str = "/// Adds two numbers.
+/// Returns their sum.
+fun add(a, b: Number): Number { return a + b; }
+
+/// The answer.
+const ANSWER = 42;
+
+/// A point.
+record Point(x, y);
+
+var undocumented = 1;
+
+/// Not attached to a statement.
+print 1;
+
+/// Shapes.
+enum Shape { Circle(r), Empty }
+";
+
+fn script(name: &str, source: &str) -> std::path::PathBuf {
+    let path = scratch_dir(name).join("utils.lox");
+    std::fs::write(&path, source).expect("write script");
+    path
+}
+
+#[test]
+fn markdown_lists_every_declaration_with_its_docs() {
+    let path = script("doc_markdown", SOURCE);
+    let result = lox(&[Path::new("doc"), &path]);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.code, Some(0));
+    assert_eq!(result.stdout, "# utils
+
+## add
+
+```lox
+fun add(a, b: Number): Number
+```
+
+Adds two numbers.
+Returns their sum.
+
+## ANSWER
+
+```lox
+const ANSWER
+```
+
+The answer.
+
+## Point
+
+```lox
+record Point(x, y)
+```
+
+A point.
+
+## undocumented
+
+```lox
+var undocumented
+```
+
+## Shape
+
+```lox
+enum Shape { Circle(r), Empty }
+```
+
+Shapes.
+
+");
+}
+
+#[test]
+fn html_pages_are_escaped_and_written_to_the_output_directory() {
+    let path = script("doc_html", "/// Returns a < b & \"c\".\nfun lt(a, b) { return a < b; }\n");
+    let out = path.parent().unwrap().join("out");
+    let result = lox(&[Path::new("doc"), Path::new("--html"), Path::new(&format!("--out={}", out.display())), &path]);
+    assert_eq!(result.code, Some(0), "{}", result.stderr);
+    let page = std::fs::read_to_string(out.join("utils.html")).expect("page written");
+    assert!(page.contains("<h2>lt</h2>\n<pre><code>fun lt(a, b)</code></pre>\n<p>Returns a &lt; b &amp; &quot;c&quot;.</p>"), "{}", page);
+}
+
+#[test]
+fn doc_errors() {
+    let path = script("doc_syntax_error", "fun {\n");
+    let result = lox(&[Path::new("doc"), &path]);
+    assert_eq!(result.code, Some(65));
+    assert!(result.stderr.contains("[line 1] Error: Expect function name."), "{}", result.stderr);
+
+    let result = lox(&["doc"]);
+    assert_eq!(result.code, Some(64));
+    assert!(result.stderr.starts_with("Usage: lox"), "{}", result.stderr);
+
+    let result = lox(&["doc", "--pdf", "x.lox"]);
+    assert_eq!(result.code, Some(64));
+    assert!(result.stderr.contains("Unknown option '--pdf'."), "{}", result.stderr);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn variants_carry_payloads_and_compare_structurally() {
+    let result = run("enum_basics", r#"
+        enum Shape { Circle(r), Rect(w, h), Empty }
+        var c = Shape.Circle(2);
+        var r = Shape.Rect(h: 3, w: 4);
+        print c;
+        print r;
+        print Shape.Empty;
+        print r.w * r.h;
+        print c == Shape.Circle(2);
+        print c == Shape.Circle(3);
+        print Shape.Empty == Shape.Empty;
+        print c is Shape.Circle;
+        print c is Shape.Rect;
+        print Shape.Empty is Shape.Empty;
+        print r is Shape;
+        print "circle" is Shape;
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "Shape.Circle(r: 2)\nShape.Rect(w: 4, h: 3)\nShape.Empty\n12\n\
+        true\nfalse\ntrue\ntrue\nfalse\ntrue\ntrue\nfalse\n");
+}
+
+#[test]
+fn separate_declarations_are_different_enums() {
+    let result = run("enum_identity", r#"
+        fun make() {
+            enum State { On, Off(reason) }
+            return State;
+        }
+        var A = make();
+        var B = make();
+        print A == B;
+        print A.On == B.On;
+        print A.Off(1) == B.Off(1);
+        print A.Off(1) == A.Off(1);
+        print A.On is B.On;
+        print A.On is B;
+        print A.On is A;
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "false\nfalse\nfalse\ntrue\nfalse\nfalse\ntrue\n");
+}
+
+#[test]
+fn enum_errors() {
+    let cases = [
+        ("enum_variant", "enum E { A }\nprint E.B;", "Enum 'E' has no variant 'B'."),
+        ("enum_field", "enum E { A(x) }\nprint E.A(1).y;", "Variant 'E.A' has no field 'y'."),
+        ("enum_arity", "enum E { A(x, y) }\nE.A(1);", "Variant 'E.A' expects 2 arguments but got 1."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn eval_returns_the_value_of_a_trailing_expression() {
+    let result = run("eval_value", "var (ok, value) = eval(\"var a = 20; a + 22\");\nprint ok;\nprint value;\n");
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "true\n42\n");
+}
+
+#[test]
+fn eval_reports_a_syntax_error_with_its_line_and_column() {
+    let result = run("eval_syntax_error", r#"
+        var (ok, error) = eval("var x = 1;
+print (x +;");
+        print ok;
+        print error["kind"];
+        print error["message"];
+        print error["line"];
+        print error["column"];
+        print "still running";
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.code, Some(0));
+    assert_eq!(result.stdout, "false\nsyntax\nExpect expression.\n2\n11\nstill running\n");
+}
+
+#[test]
+fn eval_reports_an_unexpected_character_with_its_column() {
+    let result = run("eval_bad_character", "print eval(\"1 $ 2\");\n");
+    assert_eq!(result.stdout, "(false, {\"kind\": \"syntax\", \"message\": \"Unexpected character: '$'\", \"line\": 1, \"column\": 3})\n");
+}
+
+#[test]
+fn eval_reports_a_runtime_error_without_stopping_the_script() {
+    let result = run("eval_runtime_error", r#"
+        var (ok, error) = eval("var y = 1 / nil;");
+        print ok;
+        print error["kind"];
+        print error["message"];
+        print "still running";
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.code, Some(0));
+    assert_eq!(result.stdout, "false\nruntime\nOperand must be a number for operator '/'\nstill running\n");
+}
+
+#[test]
+fn exit_inside_eval_still_exits() {
+    let result = run("eval_exit", "eval(\"exit(3);\");\nprint \"unreachable\";\n");
+    assert_eq!(result.code, Some(3));
+    assert_eq!(result.stdout, "");
+}
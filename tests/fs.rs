@@ -0,0 +1,74 @@
+mod common;
+
+use common::{run_with, scratch_dir};
+
+fn allow(dir: &std::path::Path) -> String {
+    format!("--allow-fs={}", dir.display())
+}
+
+#[test]
+fn files_are_written_read_and_removed() {
+    let dir = scratch_dir("fs_basics");
+    let source = format!(r#"
+        var root = "{}";
+        fs.mkdir(fs.join(root, "sub"));
+        fs.write_file(fs.join(root, "sub", "a.txt"), "one");
+        fs.append_file(fs.join(root, "sub", "a.txt"), "
+two");
+        print fs.read_lines(fs.join(root, "sub", "a.txt"));
+        print fs.list_dir(fs.join(root, "sub"));
+        fs.remove(fs.join(root, "sub", "a.txt"));
+        fs.remove(fs.join(root, "sub"));
+        print fs.exists(fs.join(root, "sub"));
+    "#, dir.display());
+    let result = run_with("fs_basics", &source, &[&allow(&dir)], &[]);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "(\"one\", \"two\")\n(\"a.txt\",)\nfalse\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn removing_a_symlink_keeps_its_target() {
+    let dir = scratch_dir("fs_symlink");
+    let root = dir.join("root");
+    std::fs::create_dir(&root).unwrap();
+    std::fs::write(root.join("target.txt"), "kept").unwrap();
+    std::os::unix::fs::symlink(root.join("target.txt"), root.join("link")).unwrap();
+    let outside = dir.join("outside.txt");
+    std::fs::write(&outside, "kept").unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("escape")).unwrap();
+
+    let source = format!("fs.remove(\"{0}/link\");\nfs.remove(\"{0}/escape\");\n", root.display());
+    let result = run_with("fs_symlink", &source, &[&allow(&root)], &[]);
+    assert_eq!(result.stderr, "");
+    assert!(!root.join("link").exists() && std::fs::symlink_metadata(root.join("escape")).is_err());
+    assert_eq!(std::fs::read_to_string(root.join("target.txt")).unwrap(), "kept");
+    assert_eq!(std::fs::read_to_string(&outside).unwrap(), "kept");
+}
+
+#[test]
+fn allowed_roots_cannot_be_removed() {
+    let dir = scratch_dir("fs_root");
+    let source = format!("fs.remove(\"{}\");\n", dir.display());
+    let result = run_with("fs_root", &source, &[&allow(&dir)], &[]);
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("is an allowed directory and can't be changed itself."), "{}", result.stderr);
+    assert!(dir.exists());
+
+    let source = format!("fs.remove(\"{}/sub/..\");\n", dir.display());
+    let result = run_with("fs_root_dots", &source, &[&allow(&dir)], &[]);
+    assert_eq!(result.code, Some(70));
+    assert!(dir.exists());
+}
+
+#[test]
+fn paths_outside_the_roots_are_denied() {
+    let dir = scratch_dir("fs_outside");
+    let source = format!("fs.read_file(\"{}/../elsewhere.txt\");\n", dir.display());
+    let result = run_with("fs_outside", &source, &[&allow(&dir)], &[]);
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("is outside of the allowed directories."), "{}", result.stderr);
+
+    let result = run_with("fs_disabled", "fs.exists(\".\");\n", &[], &[]);
+    assert!(result.stderr.contains("File system access is disabled"), "{}", result.stderr);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn values_survive_a_round_trip() {
+    let result = run("json_round_trip", r#"
+        var value = {"name": "lux", "tags": (1, 2.5, true, nil), "nested": {"empty": ()}};
+        var compact = json_stringify(value);
+        print compact;
+        var parsed = json_parse(compact);
+        print parsed.get("name");
+        print parsed.get("tags");
+        print json_stringify(parsed) == compact;
+        print json_parse("[1, -0.5e1, false, null, {}]");
+        print json_stringify((1, (2, 3)), 2);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "{\"name\":\"lux\",\"tags\":[1,2.5,true,null],\"nested\":{\"empty\":[]}}\n\
+        lux\n(1, 2.5, true, nil)\ntrue\n(1, -5, false, nil, {})\n\
+        [\n  1,\n  [\n    2,\n    3\n  ]\n]\n");
+}
+
+#[test]
+fn parse_errors_report_line_and_column() {
+    let result = run("json_parse_error", "json_parse(\"[1,\n  2,\n  x]\");\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("json_parse: Unexpected character 'x' at line 3, column 3."), "{}", result.stderr);
+
+    let result = run("json_trailing_data", "json_parse(\"1 2\");\n");
+    assert!(result.stderr.contains("Unexpected data after the JSON value at line 1, column 3."), "{}", result.stderr);
+}
+
+#[test]
+fn maps_that_contain_themselves_are_rejected() {
+    let result = run("json_cycle", r#"
+        var outer = {"a": 1};
+        var inner = {"outer": outer};
+        outer.set("inner", inner);
+        json_stringify(outer);
+    "#);
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("json_stringify: cannot serialize a map that contains itself."), "{}", result.stderr);
+
+    let result = run("json_shared", r#"
+        var shared = {"x": 1};
+        print json_stringify({"a": shared, "b": shared});
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "{\"a\":{\"x\":1},\"b\":{\"x\":1}}\n");
+}
+
+#[test]
+fn indent_must_be_small_and_non_negative() {
+    for (name, indent) in [("json_indent_negative", "-1"), ("json_indent_huge", "1000000000")] {
+        let result = run(name, &format!("json_stringify((1, 2), {});\n", indent));
+        assert_eq!(result.code, Some(70));
+        assert!(result.stderr.contains(&format!("indent must be between 0 and 10, got {}.", indent)), "{}", result.stderr);
+    }
+    let result = run("json_indent_fraction", "json_stringify((1, 2), 1.5);\n");
+    assert!(result.stderr.contains("'json_stringify' expects an integer"), "{}", result.stderr);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn labels_target_outer_loops() {
+    let result = run("loop_labels", r#"
+        outer: for (var i = 0; i < 3; i = i + 1) {
+            for (var j = 0; j < 3; j = j + 1) {
+                if (j == 1) { continue outer; }
+                if (i == 2) { break outer; }
+                print (i, j);
+            }
+        }
+        var found;
+        search: while (true) {
+            var k = 0;
+            while (k < 10) {
+                k = k + 1;
+                if (k == 3) { found = k; break search; }
+            }
+        }
+        print found;
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "(0, 0)\n(1, 0)\n3\n");
+}
+
+#[test]
+fn do_while_runs_its_body_at_least_once() {
+    let result = run("do_while", r#"
+        var n = 0;
+        do { n = n + 1; } while (n < 0);
+        print n;
+        var i = 0;
+        do {
+            i = i + 1;
+            if (i == 2) { continue; }
+            print i;
+        } while (i < 4);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "1\n1\n3\n4\n");
+}
+
+#[test]
+fn bad_jumps_are_static_errors() {
+    let cases = [
+        ("jump_undefined_label", "while (true) {\n  break nowhere;\n}", "[line 2] Error: Undefined loop label 'nowhere' for 'break'."),
+        ("jump_through_function", "outer: while (true) {\n  fun f() { continue outer; }\n  break;\n}", "[line 2] Error: Cannot use 'continue' outside of a loop."),
+        ("jump_outside_loop", "break;", "[line 1] Error: Cannot use 'break' outside of a loop."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(65), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
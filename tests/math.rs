@@ -0,0 +1,52 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn math_functions() {
+    let result = run("math_functions", r#"
+        print math.sqrt(16);
+        print math.pow(2, 10);
+        print math.abs(-3);
+        print (math.floor(-1.5), math.ceil(-1.5), math.round(2.5), math.round(-2.5));
+        print math.min(3, 1, 2);
+        print math.max(3, 1, 2);
+        print math.hypot(3, 4);
+        print math.log(8, 2);
+        print math.log(1);
+        print math.exp(0);
+        print math.sin(0) + math.cos(0) + math.tan(0);
+        print math.atan2(0, 1);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "4\n1024\n3\n(-2, -1, 3, -3)\n1\n3\n5\n3\n0\n1\n1\n0\n");
+}
+
+#[test]
+fn constants_and_special_values() {
+    let result = run("math_constants", r#"
+        print math.floor(math.PI * 1000);
+        print math.floor(math.E * 1000);
+        print (math.INF, -math.INF, math.NAN);
+        print math.max(1, math.NAN);
+        print math.sqrt(-1);
+        print (math.is_nan(math.NAN), math.is_nan(1));
+        print (math.is_finite(math.INF), math.is_finite(1));
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "3141\n2718\n(inf, -inf, nan)\nnan\nnan\n(true, false)\n(false, true)\n");
+}
+
+#[test]
+fn math_argument_errors() {
+    let cases = [
+        ("math_type", "math.sqrt(\"4\");", "'sqrt' expects a number but got \"4\"."),
+        ("math_arity", "math.pow(1);", "Function 'pow' expects 2 arguments but got 1."),
+        ("math_min_type", "math.min(1, nil);", "'min' expects a number but got nil."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
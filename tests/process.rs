@@ -0,0 +1,69 @@
+mod common;
+
+use common::{run, run_with};
+
+#[test]
+fn script_arguments_follow_the_script_path() {
+    let result = run_with("process_args", "print args;\n", &[], &["a", "b c"]);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "(\"a\", \"b c\")\n");
+
+    let result = run("process_no_args", "print args;\n");
+    assert_eq!(result.stdout, "()\n");
+}
+
+#[test]
+fn environment_variables_can_be_read_and_changed() {
+    let result = run("process_env", r#"
+        set_env("LUX_TEST_VARIABLE", "v1");
+        print env("LUX_TEST_VARIABLE");
+        set_env("LUX_TEST_VARIABLE", nil);
+        print env("LUX_TEST_VARIABLE");
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "v1\nnil\n");
+}
+
+#[test]
+fn exit_sets_the_status_and_stops_the_script() {
+    let result = run("process_exit", "print \"before\";\nexit(3);\nprint \"after\";\n");
+    assert_eq!(result.code, Some(3));
+    assert_eq!(result.stdout, "before\n");
+
+    let result = run("process_exit_default", "exit();\n");
+    assert_eq!(result.code, Some(0));
+
+    let result = run("process_exit_range", "exit(4294967296);\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("'exit' got an out of range status 4294967296."), "{}", result.stderr);
+}
+
+#[cfg(unix)]
+#[test]
+fn run_captures_output_and_status() {
+    let source = "print run(\"sh\", (\"-c\", \"echo hi; echo err >&2; exit 4\"));\n";
+    let result = run_with("process_run", source, &["--allow-run"], &[]);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "{\"code\": 4, \"stdout\": \"hi\\n\", \"stderr\": \"err\\n\"}\n");
+}
+
+#[test]
+fn process_errors() {
+    let result = run("process_run_denied", "run(\"true\");\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("Spawning processes is disabled, run with --allow-run to enable it."), "{}", result.stderr);
+
+    let result = run_with("process_run_missing", "run(\"lux-no-such-program\");\n", &["--allow-run"], &[]);
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("run: 'lux-no-such-program':"), "{}", result.stderr);
+
+    let result = run_with("process_run_arguments", "run(\"true\", \"-x\");\n", &["--allow-run"], &[]);
+    assert!(result.stderr.contains("'run' expects a tuple of arguments but got \"-x\"."), "{}", result.stderr);
+
+    let result = run("process_set_env_name", "set_env(\"A=B\", \"x\");\n");
+    assert!(result.stderr.contains("'set_env' got an invalid variable name \"A=B\"."), "{}", result.stderr);
+
+    let result = run_with("process_unknown_option", "print 1;\n", &["--allow-everything"], &[]);
+    assert_eq!(result.code, Some(64));
+    assert!(result.stderr.contains("Unknown option '--allow-everything'."), "{}", result.stderr);
+}
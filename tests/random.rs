@@ -0,0 +1,55 @@
+mod common;
+
+use common::{run, run_with};
+
+const SCRIPT: &str = r#"
+    print random_int(1, 100);
+    print choice("abc");
+    print shuffle((1, 2, 3, 4, 5));
+    print shuffle("hello");
+"#;
+
+#[test]
+fn the_seed_flag_makes_runs_reproducible() {
+    let first = run_with("random_seed_flag", SCRIPT, &["--seed=3"], &[]);
+    assert_eq!(first.stderr, "");
+    // Pinned so that a change to the generator or to how it is used shows up here
+    assert_eq!(first.stdout, "9\nb\n(5, 2, 4, 3, 1)\neholl\n");
+    let second = run_with("random_seed_flag_again", SCRIPT, &["--seed=3"], &[]);
+    assert_eq!(second.stdout, first.stdout);
+}
+
+#[test]
+fn seed_restarts_the_sequence() {
+    let result = run("random_seed_call", r#"
+        seed(5);
+        var a = (random(), random_int(-10, 10), choice((1, 2, 3)));
+        seed(5);
+        print a == (random(), random_int(-10, 10), choice((1, 2, 3)));
+        var x = random();
+        print x >= 0 && x < 1;
+        print random_int(4, 4);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "true\ntrue\n4\n");
+}
+
+#[test]
+fn random_errors() {
+    let cases = [
+        ("random_bounds", "random_int(3, 1);", "'random_int' expects lo <= hi, got 3 and 1."),
+        ("random_fraction", "random_int(0.5, 1);", "'random_int' expects an integer"),
+        ("random_empty_choice", "choice(());", "'choice' cannot pick from an empty sequence."),
+        ("random_shuffle_type", "shuffle(3);", "'shuffle' expects a tuple or a string but got 3."),
+        ("random_seed_type", "seed(\"x\");", "'seed' expects an integer but got \"x\"."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+
+    let result = run_with("random_bad_flag", "print 1;", &["--seed=x"], &[]);
+    assert_eq!(result.code, Some(64));
+    assert!(result.stderr.contains("--seed expects an integer, got 'x'."), "{}", result.stderr);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn records_construct_compare_and_print() {
+    let result = run("record_basics", r#"
+        record Point(x, y);
+        var p = Point(1, 2);
+        print p;
+        print p.x + p.y;
+        print Point(y: 2, x: 1) == p;
+        print Point(1, 3) == p;
+        print p with { y: 5 };
+        print p;
+        print p is Point;
+        print 1 is Point;
+        print Point;
+        print Point(1, (2, "a"));
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "Point(x: 1, y: 2)\n3\ntrue\nfalse\nPoint(x: 1, y: 5)\nPoint(x: 1, y: 2)\n\
+        true\nfalse\n<record Point>\nPoint(x: 1, y: (2, \"a\"))\n");
+}
+
+#[test]
+fn separate_declarations_are_different_types() {
+    let result = run("record_identity", r#"
+        fun make() {
+            record P(x);
+            return P;
+        }
+        var A = make();
+        var B = make();
+        print A == B;
+        print A == A;
+        print A(1) == B(1);
+        print A(1) == A(1);
+        print A(1) is B;
+        print A(1) is A;
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "false\ntrue\nfalse\ntrue\nfalse\ntrue\n");
+}
+
+#[test]
+fn record_errors() {
+    let cases = [
+        ("record_arity", "record P(x, y);\nP(1);", "Record 'P' expects 2 arguments but got 1."),
+        ("record_field", "record P(x);\nprint P(1).y;", "Record 'P' has no field 'y'."),
+        ("record_with_field", "record P(x);\nprint P(1) with { z: 2 };", "Record 'P' has no field 'z'."),
+        ("record_is_operand", "record P(x);\nprint P(1) is 3;", "Right operand of 'is' must be a record, an enum or a variant, got 3."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn type_names_match_the_type_checker() {
+    let result = run("reflect_types", r#"
+        record Point(x, y);
+        enum Shape { Circle(r), Empty }
+        fun f() {}
+        print (type(1), type("s"), type(true), type(nil), type((1,)), type(Set()), type({}));
+        print (type(math), type(f), type(clock), type(Point), type(Point(1, 2)));
+        print (type(Shape), type(Shape.Empty), type(Shape.Circle));
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "(\"Number\", \"String\", \"Boolean\", \"Nil\", \"Tuple\", \"Set\", \"Map\")\n\
+        (\"Namespace\", \"Function\", \"Function\", \"record Point\", \"Point\")\n\
+        (\"enum Shape\", \"Shape\", \"Function\")\n");
+}
+
+#[test]
+fn fields_list_declared_names() {
+    let result = run("reflect_fields", r#"
+        record Point(x, y);
+        enum Shape { Circle(r), Empty }
+        print fields(Point(1, 2));
+        print fields(Point);
+        print fields(Shape);
+        print fields(Shape.Circle(1));
+        print fields(Shape.Empty);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "(\"x\", \"y\")\n(\"x\", \"y\")\n(\"Circle\", \"Empty\")\n(\"r\",)\n()\n");
+}
+
+#[test]
+fn environments_can_be_inspected() {
+    let result = run("reflect_environment", r#"
+        var top = 1;
+        print locals();
+        print defined("top");
+        print defined("nope");
+        fun f(a) {
+            var b = 2;
+            {
+                var a = 3;
+                print locals();
+                print defined("b");
+            }
+        }
+        f(1);
+        print defined("b");
+        var all = globals();
+        print all.get("top");
+        print type(all.get("print_globals_missing", "default"));
+        print type(all.get("globals"));
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "{}\ntrue\nfalse\n{\"a\": 3, \"b\": 2}\ntrue\nfalse\n1\nString\nFunction\n");
+}
+
+#[test]
+fn reflection_errors() {
+    let result = run("reflect_fields_type", "fields(1);\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("'fields' expects a record, variant, enum or namespace but got 1."), "{}", result.stderr);
+
+    let result = run("reflect_defined_type", "defined(1);\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("'defined' expects a string but got 1."), "{}", result.stderr);
+}
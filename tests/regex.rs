@@ -0,0 +1,53 @@
+#![cfg(feature = "regex")]
+
+mod common;
+
+use common::run;
+
+#[test]
+fn matching_and_captures() {
+    let result = run("regex_match", r#"
+        print regex_match("(\w+)@(\w+)\.com", "mail ann@example.com now");
+        print regex_match("x(y)?", "x");
+        print regex_match("\d", "none");
+        print regex_find_all("\d+", "a1 b22 c333");
+        print regex_find_all("(\w)=(\d)", "a=1, b=2");
+        print regex_find_all("z", "abc");
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "(\"ann@example.com\", \"ann\", \"example\")\n(\"x\", nil)\nnil\n\
+        (\"1\", \"22\", \"333\")\n((\"a=1\", \"a\", \"1\"), (\"b=2\", \"b\", \"2\"))\n()\n");
+}
+
+#[test]
+fn replacing_and_splitting() {
+    let result = run("regex_replace", r#"
+        print regex_replace("(\w+) (\w+)", "hello world", "$2 $1");
+        print regex_replace("(?P<n>\d)", "a1b2", "<${n}>");
+        print regex_split("\s*,\s*", "a , b,c");
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "world hello\na<1>b<2>\n(\"a\", \"b\", \"c\")\n");
+}
+
+#[test]
+fn nested_repetition_runs_in_linear_time() {
+    let text = "a".repeat(50_000);
+    let result = run("regex_linear", &format!("print regex_match(\"(a+)+$\", \"{}b\");\n", text));
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "nil\n");
+}
+
+#[test]
+fn regex_errors() {
+    let cases = [
+        ("regex_invalid", "regex_match(\"(\", \"x\");", "regex_match: invalid pattern:"),
+        ("regex_pattern_type", "regex_split(1, \"x\");", "'regex_split' expects a string but got 1."),
+        ("regex_text_type", "regex_find_all(\"a\", nil);", "'regex_find_all' expects a string but got nil."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn sets_deduplicate_in_insertion_order() {
+    let result = run("set_basics", r#"
+        var s = Set(3, 1, 3, 2);
+        print s;
+        print s.len();
+        print 2 in s;
+        print 5 in s;
+        print s.contains(1);
+        print s.add(5);
+        print s.remove(3);
+        print s;
+        print Set(1, 2) == Set(2, 1);
+        print Set().to_tuple();
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "Set(3, 1, 2)\n3\ntrue\nfalse\ntrue\nSet(3, 1, 2, 5)\nSet(1, 2)\nSet(3, 1, 2)\ntrue\n()\n");
+}
+
+#[test]
+fn set_algebra() {
+    let result = run("set_algebra", r#"
+        print Set(1, 2).union(Set(2, 3));
+        print Set(1, 2, 3).intersection(Set(3, 2, 9));
+        print Set(1, 2, 3).difference(Set(2));
+        print Set(1, 2).is_subset(Set(1, 2, 3));
+        print Set(1, 4).is_subset(Set(1, 2, 3));
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "Set(1, 2, 3)\nSet(2, 3)\nSet(1, 3)\ntrue\nfalse\n");
+}
+
+#[test]
+fn zeros_and_nans_are_single_elements() {
+    let result = run("set_numbers", r#"
+        print Set(0, -0);
+        print Set(0/0, 0/0).len();
+        print Set((1, "a"), (1, "a")).len();
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "Set(0)\n1\n1\n");
+}
+
+#[test]
+fn set_errors() {
+    let cases = [
+        ("set_unhashable", "Set({\"a\": 1});", "Set elements must be nil, booleans, numbers, strings or tuples of those, got {\"a\": 1}."),
+        ("set_union_type", "Set(1).union(2);", "'union' expects a set but got 2."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn strings_and_tuples_slice_like_python() {
+    let result = run("slice_basics", r#"
+        print "abcdef"[1:4];
+        print "abcdef"[-2:];
+        print "abcdef"[::-1];
+        print "abcdef"[-100:100:2];
+        print (1, 2, 3, 4)[5:0:-2];
+        print "héllo"[1];
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "bcd\nef\nfedcba\nace\n(4, 2)\né\n");
+}
+
+#[test]
+fn a_huge_step_selects_only_the_start() {
+    let result = run("slice_huge_step", "print \"abcdef\"[1::9223372036854774784];\nprint \"abcdef\"[::-9223372036854774784];\n");
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "b\nf\n");
+}
+
+#[test]
+fn values_beyond_the_integer_range_are_rejected() {
+    let result = run("slice_out_of_range", "print \"abcdef\"[1::9223372036854775807];\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("Slice step must be an integer between -2^63 and 2^63"), "{}", result.stderr);
+
+    let result = run("index_out_of_range", "print \"abc\"[100000000000000000000];\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("Index must be an integer between -2^63 and 2^63"), "{}", result.stderr);
+}
+
+#[test]
+fn zero_steps_and_bad_indexes_are_errors() {
+    let result = run("slice_zero_step", "print (1, 2)[::0];\n");
+    assert!(result.stderr.contains("Slice step cannot be zero."), "{}", result.stderr);
+
+    let result = run("index_past_end", "print (1, 2)[2];\n");
+    assert!(result.stderr.contains("Index 2 out of range for length 2."), "{}", result.stderr);
+}
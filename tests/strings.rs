@@ -0,0 +1,66 @@
+mod common;
+
+use common::run;
+
+fn runtime_error(name: &str, source: &str) -> String {
+    let result = run(name, source);
+    assert_eq!(result.code, Some(70), "{}", result.stderr);
+    result.stderr
+}
+
+#[test]
+fn string_methods() {
+    let result = run("string_methods", r#"
+        print "héllo".len();
+        print "MiXed".upper() + "MiXed".lower();
+        print "[" + "  x  ".trim() + "]";
+        print "a b  c".split();
+        print "a,b,,c".split(",");
+        print "-".join(("a", "b", "c"));
+        print "a.b.c".replace(".", "/");
+        print "héllo".find("llo");
+        print "hello".find("z");
+        print "hello".starts_with("he");
+        print "hello".ends_with("he");
+        print "7".pad_left(3, "0");
+        print "[" + "ab".pad_left(4) + "]";
+        print "long".pad_left(2);
+        print "ab".repeat(3);
+        print "[" + "ab".repeat(0) + "]";
+        print "hé".chars();
+        print "a
+        b".lines();
+        print " 2.5 ".to_number();
+        print "two".to_number();
+        print str(1.5) + str(true);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "5\nMIXEDmixed\n[x]\n(\"a\", \"b\", \"c\")\n(\"a\", \"b\", \"\", \"c\")\na-b-c\na/b/c\n2\n-1\n\
+        true\nfalse\n007\n[  ab]\nlong\nababab\n[]\n(\"h\", \"é\")\n(\"a\", \"        b\")\n2.5\nnil\n1.5true\n");
+}
+
+#[test]
+fn string_method_argument_errors() {
+    let cases = [
+        ("split_empty", "\"ab\".split(\"\");", "'split' separator cannot be empty"),
+        ("split_type", "\"ab\".split(1);", "'split' expects a string but got 1"),
+        ("join_type", "\",\".join(1);", "'join' expects a tuple or a set of strings"),
+        ("join_items", "\",\".join((\"a\", 2));", "'join' expects a string but got 2"),
+        ("replace_empty", "\"ab\".replace(\"\", \"x\");", "'replace' pattern cannot be empty"),
+        ("find_type", "\"ab\".find(nil);", "'find' expects a string but got nil"),
+        ("starts_with_type", "\"ab\".starts_with(1);", "'starts_with' expects a string"),
+        ("ends_with_type", "\"ab\".ends_with(1);", "'ends_with' expects a string"),
+        ("pad_left_width", "\"ab\".pad_left(1.5);", "'pad_left' expects an integer"),
+        ("pad_left_fill", "\"ab\".pad_left(4, \"xy\");", "'pad_left' fill must be a single character"),
+        ("pad_left_huge", "\"ab\".pad_left(9223372036854774784);", "'pad_left' result would be longer than 16777216 bytes"),
+        ("repeat_negative", "\"ab\".repeat(-1);", "'repeat' count cannot be negative, got -1"),
+        ("repeat_huge", "\"ab\".repeat(9223372036854774784);", "'repeat' result would be longer than 16777216 bytes"),
+        ("repeat_type", "\"ab\".repeat(\"2\");", "'repeat' expects an integer"),
+        ("len_arity", "\"ab\".len(1);", "Method 'len' expects 0 arguments but got 1"),
+        ("format_pattern", "format(1);", "'format' expects a string but got 1"),
+    ];
+    for (name, source, message) in cases {
+        let stderr = runtime_error(name, source);
+        assert!(stderr.contains(message), "{}: {}", name, stderr);
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::run;
+
+// The test harness captures stdout, so the interpreter never sees a terminal here
+
+#[test]
+fn styling_degrades_to_plain_text_without_a_terminal() {
+    let result = run("term_plain", r#"
+        print term.is_tty();
+        print term.color("error", "red");
+        print term.color(42, "bright_cyan");
+        print term.color("x", 208);
+        print term.background("bg", "blue");
+        print term.style("title", "bold", "underline");
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "false\nerror\n42\nx\nbg\ntitle\n");
+}
+
+#[test]
+fn control_sequences_are_not_written_to_redirected_output() {
+    let result = run("term_controls", r#"
+        term.clear();
+        term.move_to(3, 4);
+        term.up();
+        term.down(2);
+        term.left(0);
+        term.right(5);
+        term.clear_line();
+        term.hide_cursor();
+        term.show_cursor();
+        term.raw_mode(false);
+        print "done";
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "done\n");
+}
+
+#[test]
+fn size_reports_columns_and_rows() {
+    let result = run("term_size", r#"
+        var size = term.size();
+        print size.get("columns") > 0 && size.get("rows") > 0;
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "true\n");
+}
+
+#[test]
+fn terminal_argument_errors() {
+    let cases = [
+        ("term_color_name", "term.color(\"x\", \"pink\");", "term.color: unknown colour 'pink', expected one of black, red, green"),
+        ("term_palette", "term.background(\"x\", 256);", "term.background: palette index 256 is not in 0..255."),
+        ("term_style", "term.style(\"x\", \"loud\");", "term.style: unknown style 'loud'"),
+        ("term_move_to", "term.move_to(0, 1);", "term.move_to: expected a number of at least 1 but got 0."),
+        ("term_up", "term.up(-1);", "term.up: expected a number of at least 0 but got -1."),
+        ("term_raw_mode", "term.raw_mode(\"yes\");", "term.raw_mode expects true or false but got \"yes\"."),
+    ];
+    for (name, source, message) in cases {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{lox, run, scratch_dir};
+use std::path::Path;
+
+fn write(dir: &Path, name: &str, source: &str) {
+    std::fs::write(dir.join(name), source).expect("write test file");
+}
+
+#[test]
+fn passing_tests_exit_cleanly() {
+    let dir = scratch_dir("runner_pass");
+    write(&dir, "math.lox", r#"
+        fun add(a, b) { return a + b; }
+        test "adds" {
+            assert add(1, 2) == 3;
+        }
+        test "has its own globals" {
+            var fresh = 1;
+            assert fresh == 1, "fresh";
+        }
+    "#);
+    write(&dir, "notes.txt", "not lox");
+    let result = lox(&[Path::new("test"), &dir]);
+    assert_eq!(result.code, Some(0), "{}", result.stdout);
+    assert!(result.stdout.contains("  ok    adds\n  ok    has its own globals\n"), "{}", result.stdout);
+    assert!(result.stdout.ends_with("\n2 passed, 0 failed\n"), "{}", result.stdout);
+}
+
+#[test]
+fn failures_are_counted_and_exit_non_zero() {
+    let dir = scratch_dir("runner_fail");
+    write(&dir, "a.lox", r#"test "ok" {
+    assert 1 == 1;
+}
+test "wrong sum" {
+    assert 1 + 2 == 4, "bad sum";
+}
+test "runtime error" {
+    var x = nil + 1;
+}
+test "exits" {
+    exit(2);
+}
+"#);
+    write(&dir, "b.lox", "test \"broken\" {\n  assert ;\n}\n");
+    let result = lox(&[Path::new("test"), &dir]);
+    assert_eq!(result.code, Some(1));
+    assert!(result.stdout.contains("  FAIL  wrong sum\n        Assertion failed on line 5: bad sum\n          left:  3\n          right: 4\n"), "{}", result.stdout);
+    assert!(result.stdout.contains("  FAIL  runtime error\n        Operand must be a number for operator '+'"), "{}", result.stdout);
+    assert!(result.stdout.contains("  FAIL  exits\n        exited with status 2\n"), "{}", result.stdout);
+    assert!(result.stdout.contains("FAIL ") && result.stdout.contains("b.lox: errors during parsing"), "{}", result.stdout);
+    assert!(result.stdout.ends_with("\n1 passed, 4 failed\n"), "{}", result.stdout);
+}
+
+#[test]
+fn a_normal_run_skips_test_blocks() {
+    let result = run("runner_skipped", "print \"main\";\ntest \"never\" {\n  print \"inside\";\n}\n");
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "main\n");
+}
+
+#[test]
+fn failed_asserts_outside_tests_are_runtime_errors() {
+    let result = run("runner_assert", "\nassert 1 < 0;\n");
+    assert_eq!(result.code, Some(70));
+    assert!(result.stderr.contains("Assertion failed on line 2\n  left:  1\n  right: 0"), "{}", result.stderr);
+}
+
+#[test]
+fn missing_paths_are_usage_errors() {
+    let dir = scratch_dir("runner_missing");
+    let result = lox(&[Path::new("test"), &dir.join("nope.lox")]);
+    assert_eq!(result.code, Some(64));
+    assert!(result.stderr.contains("Cannot read"), "{}", result.stderr);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn tuples_group_and_compare_structurally() {
+    let result = run("tuple_basics", r#"
+        print (1, "a", (true, nil));
+        print (1,);
+        print (1);
+        print ();
+        print (1, 2) == (1, 2);
+        print (1, 2) == (2, 1);
+        print (1, (2, 3)) == (1, (2, 3));
+        print (1, 2)[0] + (1, 2)[-1];
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "(1, \"a\", (true, nil))\n(1,)\n1\n()\ntrue\nfalse\ntrue\n3\n");
+}
+
+#[test]
+fn destructuring_declares_and_assigns() {
+    let result = run("tuple_destructuring", r#"
+        var (x, y) = (1, 2);
+        print x + y;
+        (x, y) = (y, x);
+        print (x, y);
+        var (single,) = ("only",);
+        print single;
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.stdout, "3\n(2, 1)\nonly\n");
+}
+
+#[test]
+fn arity_mismatches_are_runtime_errors() {
+    for (name, source) in [
+        ("tuple_declare_arity", "var (a, b) = (1, 2, 3);"),
+        ("tuple_assign_arity", "var a; var b; (a, b) = (1,);"),
+        ("tuple_not_a_tuple", "var (a, b) = 5;"),
+    ] {
+        let result = run(name, source);
+        assert_eq!(result.code, Some(70), "{}: {}", name, result.stderr);
+        assert!(result.stderr.starts_with("Runtime Error:"), "{}: {}", name, result.stderr);
+    }
+}
+
+#[test]
+fn duplicate_names_are_rejected() {
+    let result = run("tuple_duplicate_declaration", "\nvar (x, x) = (1, 2);\n");
+    assert_eq!(result.code, Some(65));
+    assert!(result.stderr.contains("[line 2] Error: Name 'x' appears more than once in a destructuring declaration."), "{}", result.stderr);
+
+    let result = run("tuple_duplicate_assignment", "var a;\nvar b;\n(a, b, a) = (1, 2, 3);\n");
+    assert_eq!(result.code, Some(65));
+    assert!(result.stderr.contains("[line 3] Error: Name 'a' appears more than once in a destructuring assignment."), "{}", result.stderr);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{lox, scratch_dir, Run};
+
+// `lox check --types` on `source`
+fn check_types(name: &str, source: &str) -> Run {
+    let path = scratch_dir(name).join("script.lox");
+    std::fs::write(&path, source).expect("write script");
+    lox(&["check".as_ref(), "--types".as_ref(), path.as_os_str()])
+}
+
+fn assert_type_error(name: &str, source: &str, message: &str) {
+    let result = check_types(name, source);
+    assert_eq!(result.code, Some(65), "{}: {}", name, result.stderr);
+    assert!(result.stderr.contains(message), "{}: {}", name, result.stderr);
+}
+
+#[test]
+fn well_typed_scripts_pass() {
+    let result = check_types("types_ok", r#"
+        var x: Number = 1;
+        var name = "lux";
+        var anything;
+        anything = "a";
+        anything = 2;
+        fun greet(who: String): String {
+            return "hi " + who;
+        }
+        print greet(name) + "!";
+        print x * 2 - anything;
+        record P(x, y);
+        var p: P = P(1, 2);
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.code, Some(0));
+    assert!(result.stdout.starts_with("No errors found in"), "{}", result.stdout);
+}
+
+#[test]
+fn annotated_mismatches_are_reported() {
+    assert_type_error("types_annotated_var", "var x: Number = \"a\";", "[line 1] Error: Type error: Variable 'x' expects Number but got String.");
+    assert_type_error("types_annotated_assign", "var x: String = \"a\";\nx = true;", "[line 2] Error: Type error: Variable 'x' expects String but got Boolean.");
+    assert_type_error("types_argument", "fun f(n: Number) { return n; }\nf(\"a\");", "Argument 1 expects Number but got String.");
+    assert_type_error("types_return", "fun f(): Number { return \"a\"; }", "Return value of 'f' expects Number but got String.");
+    assert_type_error("types_arity", "fun f(a, b) { }\nf(1);", "Expected 2 arguments but got 1.");
+    assert_type_error("types_unknown", "var x: Nmber = 1;", "Unknown type 'Nmber'.");
+}
+
+#[test]
+fn unannotated_literals_are_inferred() {
+    assert_type_error("types_inferred_string", "var z = \"a\";\nprint z - 1;", "[line 2] Error: Type error: Operator '-' expects Number but got String.");
+    assert_type_error("types_inferred_boolean", "var ok = true;\nprint ok < 1;", "Operator '<' expects Number but got Boolean.");
+    assert_type_error("types_inferred_assign", "var n = 1;\nn = \"one\";", "Variable 'n' expects Number but got String.");
+    assert_type_error("types_literal_operands", "print \"a\" - 1;", "Operator '-' expects Number but got String.");
+}
+
+#[test]
+fn values_of_unknown_type_are_not_flagged() {
+    let result = check_types("types_gradual", r#"
+        var later = nil;
+        later = "text";
+        var copy = later;
+        print copy - 1;
+        fun id(v) { return v; }
+        print id("a") - 1;
+    "#);
+    assert_eq!(result.stderr, "");
+    assert_eq!(result.code, Some(0));
+}
+
+#[test]
+fn the_checker_is_opt_in() {
+    let path = scratch_dir("types_opt_in").join("script.lox");
+    std::fs::write(&path, "var x: Number = \"a\";\n").unwrap();
+    let result = lox(&["check".as_ref(), path.as_os_str()]);
+    assert_eq!(result.code, Some(0), "{}", result.stderr);
+}